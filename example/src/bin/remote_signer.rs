@@ -0,0 +1,98 @@
+//! Executing a swap with a key held by a signing service, e.g. Fireblocks or a KMS
+
+use std::env;
+
+use jupiter_swap_api_client::{
+    execute::{execute_swap, ExecuteConfig},
+    quote::QuoteRequest,
+    signer::{SignFuture, TransactionSigner},
+    JupiterError, JupiterSwapApiClient, LITE_SWAP_API_URL,
+};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    pubkey,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+};
+use tokio::sync::{mpsc, oneshot};
+
+const USDC_MINT: Pubkey = pubkey!("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+const NATIVE_MINT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
+
+type SignRequest = (Vec<u8>, oneshot::Sender<Signature>);
+
+/// Stand-in for the signing service, the key never leaves it
+fn spawn_signing_service(keypair: Keypair) -> mpsc::Sender<SignRequest> {
+    let (sender, mut receiver) = mpsc::channel::<SignRequest>(16);
+    tokio::spawn(async move {
+        while let Some((message, reply)) = receiver.recv().await {
+            let _ = reply.send(keypair.sign_message(&message));
+        }
+    });
+    sender
+}
+
+/// Forwards messages to the signing service, replace the channel with its API client
+struct RemoteSigner {
+    pubkey: Pubkey,
+    service: mpsc::Sender<SignRequest>,
+}
+
+impl TransactionSigner for RemoteSigner {
+    fn public_key(&self) -> Pubkey {
+        self.pubkey
+    }
+
+    fn sign<'a>(&'a self, message: &'a [u8]) -> SignFuture<'a> {
+        Box::pin(async move {
+            let (reply, signature) = oneshot::channel();
+            self.service
+                .send((message.to_vec(), reply))
+                .await
+                .map_err(|e| JupiterError::Signing(format!("signing service down: {e}")))?;
+            signature.await.map_err(|e| {
+                JupiterError::Signing(format!("no signature from the signing service: {e}"))
+            })
+        })
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let api_base_url = env::var("API_BASE_URL").unwrap_or(LITE_SWAP_API_URL.into());
+    let jupiter_swap_api_client = JupiterSwapApiClient::new(api_base_url);
+
+    let keypair = Keypair::new();
+    let signer = RemoteSigner {
+        pubkey: keypair.pubkey(),
+        service: spawn_signing_service(keypair),
+    };
+
+    let quote_request = QuoteRequest::builder(USDC_MINT, NATIVE_MINT, 1_000_000)
+        .slippage_bps(50)
+        .build()
+        .unwrap();
+    let quote_response = jupiter_swap_api_client.quote(&quote_request).await.unwrap();
+    let swap_request = quote_response
+        .into_swap_request(signer.public_key())
+        .build()
+        .unwrap();
+    let swap_response = jupiter_swap_api_client
+        .swap(&swap_request, None)
+        .await
+        .unwrap();
+
+    let rpc_client = RpcClient::new("https://api.mainnet-beta.solana.com".into());
+
+    // This will fail in preflight as the new keypair holds no USDC
+    let error = execute_swap(
+        &swap_response,
+        &signer,
+        &rpc_client,
+        ExecuteConfig::default(),
+    )
+    .await
+    .unwrap_err();
+    println!("{error}");
+}
@@ -1,8 +1,8 @@
 use std::env;
 
 use jupiter_swap_api_client::{
-    quote::QuoteRequest, swap::SwapRequest, transaction_config::TransactionConfig,
-    JupiterSwapApiClient,
+    dex::Dex, quote::QuoteRequest, swap::SwapRequest, transaction_config::TransactionConfig,
+    JupiterSwapApiClient, LITE_SWAP_API_URL,
 };
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::{pubkey, transaction::VersionedTransaction};
@@ -15,19 +15,16 @@ pub const TEST_WALLET: Pubkey = pubkey!("2AQdpHJ2JpcEgPiATUXjQxA8QmafFegfQwSLWSp
 
 #[tokio::main]
 async fn main() {
-    let api_base_url = env::var("API_BASE_URL").unwrap_or("https://quote-api.jup.ag/v6".into());
+    let api_base_url = env::var("API_BASE_URL").unwrap_or(LITE_SWAP_API_URL.into());
     println!("Using base url: {}", api_base_url);
 
     let jupiter_swap_api_client = JupiterSwapApiClient::new(api_base_url);
 
-    let quote_request = QuoteRequest {
-        amount: 1_000_000,
-        input_mint: USDC_MINT,
-        output_mint: NATIVE_MINT,
-        dexes: Some("Whirlpool,Meteora DLMM,Raydium CLMM".into()),
-        slippage_bps: 50,
-        ..QuoteRequest::default()
-    };
+    let quote_request = QuoteRequest::builder(USDC_MINT, NATIVE_MINT, 1_000_000)
+        .dexes([Dex::Whirlpool, Dex::MeteoraDlmm, Dex::RaydiumClmm])
+        .slippage_bps(50)
+        .build()
+        .unwrap();
 
     // GET /quote
     let quote_response = jupiter_swap_api_client.quote(&quote_request).await.unwrap();
@@ -48,8 +45,7 @@ async fn main() {
 
     println!("Raw tx len: {}", swap_response.swap_transaction.len());
 
-    let versioned_transaction: VersionedTransaction =
-        bincode::deserialize(&swap_response.swap_transaction).unwrap();
+    let versioned_transaction = swap_response.versioned_transaction().unwrap();
 
     // Replace with a keypair or other struct implementing signer
     let null_signer = NullSigner::new(&TEST_WALLET);
@@ -68,11 +64,13 @@ async fn main() {
 
     // POST /swap-instructions
     let swap_instructions = jupiter_swap_api_client
-        .swap_instructions(&SwapRequest {
-            user_public_key: TEST_WALLET,
-            quote_response,
-            config: TransactionConfig::default(),
-        })
+        .swap_instructions(
+            &quote_response
+                .into_swap_request(TEST_WALLET)
+                .build()
+                .unwrap(),
+            None,
+        )
         .await
         .unwrap();
     println!("swap_instructions: {swap_instructions:?}");
@@ -0,0 +1,77 @@
+//! Account counts, to compose the swap with other instructions within the transaction limits
+
+use std::collections::HashSet;
+
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+use crate::{quote::QuoteResponse, swap::SwapInstructionsResponse};
+
+/// Most accounts a transaction can lock, readonly or writable
+pub const MAX_TX_ACCOUNT_LOCKS: usize = 64;
+
+/// Accounts of the Jupiter program independent of the route, e.g. token programs and user token accounts
+pub const ESTIMATED_BASE_ACCOUNTS: usize = 12;
+
+/// Accounts of a typical AMM swap
+pub const ESTIMATED_ACCOUNTS_PER_AMM: usize = 12;
+
+impl QuoteResponse {
+    /// Rough estimate of the accounts locked by the swap, from the number of distinct AMMs in the route
+    ///
+    /// Use [`SwapInstructionsResponse::account_count`] for the exact count.
+    pub fn estimated_account_count(&self) -> usize {
+        let amms = self
+            .route_plan
+            .iter()
+            .map(|step| step.swap_info.amm_key)
+            .collect::<HashSet<_>>();
+        ESTIMATED_BASE_ACCOUNTS + amms.len() * ESTIMATED_ACCOUNTS_PER_AMM
+    }
+}
+
+impl SwapInstructionsResponse {
+    /// All instructions in transaction order
+    pub fn instructions(&self) -> impl Iterator<Item = &Instruction> {
+        self.compute_budget_instructions
+            .iter()
+            .chain(&self.setup_instructions)
+            .chain(&self.token_ledger_instruction)
+            .chain(&self.pre_instructions)
+            .chain(std::iter::once(&self.swap_instruction))
+            .chain(&self.cleanup_instruction)
+            .chain(&self.post_instructions)
+            .chain(&self.other_instructions)
+    }
+
+    /// Distinct accounts and programs referenced by the instructions
+    pub fn account_keys(&self) -> HashSet<Pubkey> {
+        self.instructions()
+            .flat_map(|instruction| {
+                instruction
+                    .accounts
+                    .iter()
+                    .map(|account| account.pubkey)
+                    .chain(std::iter::once(instruction.program_id))
+            })
+            .collect()
+    }
+
+    /// Accounts locked by the transaction, lookup tables only shrink its size not its locks
+    pub fn account_count(&self) -> usize {
+        self.account_keys().len()
+    }
+
+    pub fn writable_account_count(&self) -> usize {
+        self.instructions()
+            .flat_map(|instruction| &instruction.accounts)
+            .filter(|account| account.is_writable)
+            .map(|account| account.pubkey)
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// Whether `extra_accounts` more distinct accounts still fit in [`MAX_TX_ACCOUNT_LOCKS`]
+    pub fn fits_account_locks(&self, extra_accounts: usize) -> bool {
+        self.account_count() + extra_accounts <= MAX_TX_ACCOUNT_LOCKS
+    }
+}
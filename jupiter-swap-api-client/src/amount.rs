@@ -0,0 +1,255 @@
+//! Token amounts with their decimals, to convert from and to human readable amounts
+
+use std::fmt;
+
+use rust_decimal::Decimal;
+use thiserror::Error;
+
+use crate::{math, quote::QuoteRequestBuilder};
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ParseAmountError {
+    #[error("invalid amount {0}")]
+    Invalid(String),
+    #[error("amount {amount} has more than {decimals} decimals")]
+    TooManyDecimals { amount: String, decimals: u8 },
+    #[error("amount {0} doesn't fit in u64")]
+    Overflow(String),
+    #[error("amount {amount} is not in {expected}")]
+    SymbolMismatch { amount: String, expected: String },
+}
+
+/// Atomic amount of a token with its decimals, e.g. `Amount::parse("1.5", 9)` for 1.5 SOL
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Amount {
+    raw: u64,
+    decimals: u8,
+}
+
+impl Amount {
+    pub fn new(raw: u64, decimals: u8) -> Self {
+        Self { raw, decimals }
+    }
+
+    /// Parses a human readable amount like `1.5` or `1.5 SOL`
+    ///
+    /// A trailing symbol is ignored, use [`Amount::parse_in`] to check it.
+    pub fn parse(amount: &str, decimals: u8) -> Result<Self, ParseAmountError> {
+        Self::parse_with_symbol(amount, decimals).map(|(amount, _)| amount)
+    }
+
+    /// Like [`Amount::parse`], failing with [`ParseAmountError::SymbolMismatch`] for a symbol other than `symbol`
+    ///
+    /// Symbols are compared case insensitively, amounts without a symbol are accepted.
+    pub fn parse_in(amount: &str, decimals: u8, symbol: &str) -> Result<Self, ParseAmountError> {
+        match Self::parse_with_symbol(amount, decimals)? {
+            (_, Some(parsed_symbol)) if !parsed_symbol.eq_ignore_ascii_case(symbol) => {
+                Err(ParseAmountError::SymbolMismatch {
+                    amount: amount.to_string(),
+                    expected: symbol.to_string(),
+                })
+            }
+            (parsed, _) => Ok(parsed),
+        }
+    }
+
+    fn parse_with_symbol(
+        amount: &str,
+        decimals: u8,
+    ) -> Result<(Self, Option<&str>), ParseAmountError> {
+        let invalid = || ParseAmountError::Invalid(amount.to_string());
+        let mut parts = amount.split_whitespace();
+        let number = parts.next().ok_or_else(invalid)?;
+        let symbol = parts.next();
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+        let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+        if whole.is_empty() && fraction.is_empty()
+            || !whole
+                .bytes()
+                .chain(fraction.bytes())
+                .all(|b| b.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.len() > usize::from(decimals) {
+            return Err(ParseAmountError::TooManyDecimals {
+                amount: amount.to_string(),
+                decimals,
+            });
+        }
+        let overflow = || ParseAmountError::Overflow(amount.to_string());
+        let digits = format!("{whole}{fraction:0<width$}", width = usize::from(decimals));
+        let raw = digits
+            .trim_start_matches('0')
+            .parse::<u64>()
+            .or_else(|e| match e.kind() {
+                std::num::IntErrorKind::Empty => Ok(0),
+                _ => Err(overflow()),
+            })?;
+        Ok((Self { raw, decimals }, symbol))
+    }
+
+    /// Atomic amount, as sent to the API
+    pub fn raw(&self) -> u64 {
+        self.raw
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    /// In whole tokens, `None` for more than 28 decimals
+    pub fn to_decimal(&self) -> Option<Decimal> {
+        math::ui_amount(self.raw, self.decimals)
+    }
+
+    /// `None` on overflow or when the decimals differ
+    pub fn checked_add(self, other: Amount) -> Option<Amount> {
+        (self.decimals == other.decimals).then_some(())?;
+        Some(Self::new(self.raw.checked_add(other.raw)?, self.decimals))
+    }
+
+    /// `None` on underflow or when the decimals differ
+    pub fn checked_sub(self, other: Amount) -> Option<Amount> {
+        (self.decimals == other.decimals).then_some(())?;
+        Some(Self::new(self.raw.checked_sub(other.raw)?, self.decimals))
+    }
+
+    /// `self * numerator / denominator`, rounded down
+    pub fn checked_mul_div(self, numerator: u64, denominator: u64) -> Option<Amount> {
+        let raw = math::mul_div_floor(self.raw, numerator, denominator)?;
+        Some(Self::new(raw, self.decimals))
+    }
+}
+
+impl From<Amount> for u64 {
+    fn from(amount: Amount) -> Self {
+        amount.raw
+    }
+}
+
+/// Human readable, without trailing zeros, e.g. `1.5`
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let decimals = usize::from(self.decimals);
+        let digits = format!("{:0>width$}", self.raw, width = decimals + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - decimals);
+        let fraction = fraction.trim_end_matches('0');
+        if fraction.is_empty() {
+            f.write_str(whole)
+        } else {
+            write!(f, "{whole}.{fraction}")
+        }
+    }
+}
+
+impl QuoteRequestBuilder {
+    /// Replaces the atomic amount passed to [`QuoteRequest::builder`](crate::quote::QuoteRequest::builder)
+    pub fn amount(self, amount: Amount) -> Self {
+        self.raw_amount(amount.raw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_amounts() {
+        assert_eq!(Amount::parse("1.5", 9), Ok(Amount::new(1_500_000_000, 9)));
+        assert_eq!(
+            Amount::parse(" 1.5 SOL ", 9),
+            Ok(Amount::new(1_500_000_000, 9))
+        );
+        assert_eq!(Amount::parse(".5", 1), Ok(Amount::new(5, 1)));
+        assert_eq!(Amount::parse("007", 2), Ok(Amount::new(700, 2)));
+        assert_eq!(Amount::parse("0", 6), Ok(Amount::new(0, 6)));
+    }
+
+    #[test]
+    fn parse_trailing_zeros() {
+        assert_eq!(Amount::parse("1.50000", 2), Ok(Amount::new(150, 2)));
+        assert_eq!(Amount::parse("1.000", 0), Ok(Amount::new(1, 0)));
+    }
+
+    #[test]
+    fn parse_zero_decimals() {
+        assert_eq!(Amount::parse("5", 0), Ok(Amount::new(5, 0)));
+        assert_eq!(Amount::parse("5.", 0), Ok(Amount::new(5, 0)));
+        assert!(matches!(
+            Amount::parse("5.1", 0),
+            Err(ParseAmountError::TooManyDecimals { decimals: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn parse_too_many_decimals() {
+        assert!(matches!(
+            Amount::parse("1.234", 2),
+            Err(ParseAmountError::TooManyDecimals { decimals: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn parse_overflow() {
+        assert_eq!(
+            Amount::parse("18446744073709551615", 0),
+            Ok(Amount::new(u64::MAX, 0))
+        );
+        assert!(matches!(
+            Amount::parse("18446744073709551616", 0),
+            Err(ParseAmountError::Overflow(_))
+        ));
+        assert!(matches!(
+            Amount::parse("18446744073.709551616", 9),
+            Err(ParseAmountError::Overflow(_))
+        ));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for amount in ["", ".", "-1", "1,5", "1.5.0", "1e9", "1.5 SOL extra"] {
+            assert!(
+                matches!(Amount::parse(amount, 9), Err(ParseAmountError::Invalid(_))),
+                "{amount}"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_in_checks_the_symbol() {
+        assert_eq!(
+            Amount::parse_in("1.5 sol", 9, "SOL"),
+            Ok(Amount::new(1_500_000_000, 9))
+        );
+        assert_eq!(
+            Amount::parse_in("1.5", 9, "SOL"),
+            Ok(Amount::new(1_500_000_000, 9))
+        );
+        assert!(matches!(
+            Amount::parse_in("1.5 USDC", 9, "SOL"),
+            Err(ParseAmountError::SymbolMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Amount::new(1_500_000_000, 9).to_string(), "1.5");
+        assert_eq!(Amount::new(100, 2).to_string(), "1");
+        assert_eq!(Amount::new(5, 3).to_string(), "0.005");
+        assert_eq!(Amount::new(0, 6).to_string(), "0");
+        assert_eq!(Amount::new(42, 0).to_string(), "42");
+        assert_eq!(Amount::new(u64::MAX, 0).to_string(), "18446744073709551615");
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for (raw, decimals) in [(1, 9), (123_456_789, 6), (u64::MAX, 19), (10, 1)] {
+            let amount = Amount::new(raw, decimals);
+            assert_eq!(Amount::parse(&amount.to_string(), decimals), Ok(amount));
+        }
+    }
+}
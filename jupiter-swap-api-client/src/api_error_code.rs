@@ -0,0 +1,86 @@
+use std::fmt;
+
+/// `errorCode` returned by the API alongside an error message
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum JupiterApiErrorCode {
+    TokenNotTradable,
+    CouldNotFindAnyRoute,
+    NoRoutesFound,
+    RoutePlanDoesNotConsumeAllTheAmount,
+    MarketNotFound,
+    NotSupported,
+    CircularArbitrageIsDisabled,
+    CannotComputeOtherAmountThreshold,
+    /// Any code this client doesn't know about yet, empty if the API didn't return one
+    Unknown(String),
+}
+
+impl JupiterApiErrorCode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::TokenNotTradable => "TOKEN_NOT_TRADABLE",
+            Self::CouldNotFindAnyRoute => "COULD_NOT_FIND_ANY_ROUTE",
+            Self::NoRoutesFound => "NO_ROUTES_FOUND",
+            Self::RoutePlanDoesNotConsumeAllTheAmount => {
+                "ROUTE_PLAN_DOES_NOT_CONSUME_ALL_THE_AMOUNT"
+            }
+            Self::MarketNotFound => "MARKET_NOT_FOUND",
+            Self::NotSupported => "NOT_SUPPORTED",
+            Self::CircularArbitrageIsDisabled => "CIRCULAR_ARBITRAGE_IS_DISABLED",
+            Self::CannotComputeOtherAmountThreshold => "CANNOT_COMPUTE_OTHER_AMOUNT_THRESHOLD",
+            Self::Unknown(code) => code,
+        }
+    }
+}
+
+impl From<&str> for JupiterApiErrorCode {
+    fn from(code: &str) -> Self {
+        match code {
+            "TOKEN_NOT_TRADABLE" => Self::TokenNotTradable,
+            "COULD_NOT_FIND_ANY_ROUTE" => Self::CouldNotFindAnyRoute,
+            "NO_ROUTES_FOUND" => Self::NoRoutesFound,
+            "ROUTE_PLAN_DOES_NOT_CONSUME_ALL_THE_AMOUNT" => {
+                Self::RoutePlanDoesNotConsumeAllTheAmount
+            }
+            "MARKET_NOT_FOUND" => Self::MarketNotFound,
+            "NOT_SUPPORTED" => Self::NotSupported,
+            "CIRCULAR_ARBITRAGE_IS_DISABLED" => Self::CircularArbitrageIsDisabled,
+            "CANNOT_COMPUTE_OTHER_AMOUNT_THRESHOLD" => Self::CannotComputeOtherAmountThreshold,
+            _ => Self::Unknown(code.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for JupiterApiErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_codes_round_trip() {
+        for code in [
+            JupiterApiErrorCode::TokenNotTradable,
+            JupiterApiErrorCode::CouldNotFindAnyRoute,
+            JupiterApiErrorCode::NoRoutesFound,
+            JupiterApiErrorCode::RoutePlanDoesNotConsumeAllTheAmount,
+            JupiterApiErrorCode::MarketNotFound,
+            JupiterApiErrorCode::NotSupported,
+            JupiterApiErrorCode::CircularArbitrageIsDisabled,
+            JupiterApiErrorCode::CannotComputeOtherAmountThreshold,
+        ] {
+            assert_eq!(JupiterApiErrorCode::from(code.as_str()), code);
+        }
+    }
+
+    #[test]
+    fn unknown_code_is_kept() {
+        let code = JupiterApiErrorCode::from("NEW_CODE");
+        assert_eq!(code, JupiterApiErrorCode::Unknown("NEW_CODE".into()));
+        assert_eq!(code.to_string(), "NEW_CODE");
+    }
+}
@@ -0,0 +1,52 @@
+use crate::{request_options::Endpoint, LITE_SWAP_API_URL};
+
+/// Legacy swap API
+pub const V6_SWAP_API_URL: &str = "https://quote-api.jup.ag/v6";
+
+/// Layout of the swap API deployment
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ApiVersion {
+    /// Legacy `quote-api.jup.ag/v6` layout, also used by the self-hosted API
+    V6,
+    /// `lite-api.jup.ag/swap/v1` and `api.jup.ag/swap/v1`
+    #[default]
+    SwapV1,
+}
+
+impl ApiVersion {
+    /// `SwapV1` for `/swap/v1` base paths, `V6` for any other, e.g. self-hosted ones
+    pub fn from_base_path(base_path: &str) -> Self {
+        if base_path.trim_end_matches('/').ends_with("/swap/v1") {
+            Self::SwapV1
+        } else {
+            Self::V6
+        }
+    }
+
+    pub fn default_base_path(self) -> &'static str {
+        match self {
+            Self::V6 => V6_SWAP_API_URL,
+            Self::SwapV1 => LITE_SWAP_API_URL,
+        }
+    }
+
+    /// Path of a swap API endpoint relative to the base path, `None` if this layout doesn't serve it
+    pub fn path(self, endpoint: Endpoint) -> Option<&'static str> {
+        match endpoint {
+            Endpoint::Quote => Some("/quote"),
+            Endpoint::Swap => Some("/swap"),
+            Endpoint::SwapInstructions => Some("/swap-instructions"),
+            Endpoint::ProgramIdToLabel => Some("/program-id-to-label"),
+            Endpoint::IndexedRouteMap => match self {
+                Self::V6 => Some("/indexed-route-map"),
+                Self::SwapV1 => None,
+            },
+            Endpoint::Markets => Some("/markets"),
+            Endpoint::Health => match self {
+                Self::V6 => Some("/health"),
+                Self::SwapV1 => None,
+            },
+            _ => None,
+        }
+    }
+}
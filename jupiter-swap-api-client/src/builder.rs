@@ -0,0 +1,344 @@
+use std::{collections::HashMap, net::IpAddr, sync::Arc, time::Duration};
+
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    Client, Proxy,
+};
+
+use crate::{
+    api_version::ApiVersion,
+    dns::{IpVersionPreference, IpVersionResolver},
+    interceptor::Interceptor,
+    normalize_base_path,
+    rate_limit::RateLimiter,
+    request_options::{DeserializationMode, EndpointTimeouts},
+    retry::RetryPolicy,
+    JupiterError, JupiterSwapApiClient, DEFAULT_MAX_RESPONSE_BODY_SIZE, USER_AGENT,
+};
+
+/// Fluent configuration for a [`JupiterSwapApiClient`]
+#[derive(Clone)]
+pub struct JupiterSwapApiClientBuilder {
+    base_path: String,
+    api_base_path: Option<String>,
+    api_version: Option<ApiVersion>,
+    fallback_base_paths: Vec<String>,
+    api_key: Option<String>,
+    timeout: Option<Duration>,
+    user_agent: String,
+    proxies: Vec<Proxy>,
+    pool_max_idle_per_host: Option<usize>,
+    pool_idle_timeout: Option<Duration>,
+    tcp_keepalive: Option<Duration>,
+    local_address: Option<IpAddr>,
+    ip_version_preference: Option<IpVersionPreference>,
+    #[cfg(feature = "gzip")]
+    gzip: bool,
+    #[cfg(feature = "brotli")]
+    brotli: bool,
+    default_headers: Vec<(String, String)>,
+    default_query_params: HashMap<String, String>,
+    interceptors: Vec<Arc<dyn Interceptor>>,
+    generate_idempotency_keys: bool,
+    deserialization_mode: DeserializationMode,
+    check_quote_invariants: bool,
+    max_response_body_size: Option<usize>,
+    expected_api_version: Option<String>,
+    timeouts: EndpointTimeouts,
+    retry_policy: Option<RetryPolicy>,
+    rate_limit: Option<(f64, u32)>,
+}
+
+impl JupiterSwapApiClientBuilder {
+    pub fn new(base_path: impl Into<String>) -> Self {
+        Self {
+            base_path: base_path.into(),
+            api_base_path: None,
+            api_version: None,
+            fallback_base_paths: Vec::new(),
+            api_key: None,
+            timeout: None,
+            user_agent: USER_AGENT.to_string(),
+            proxies: Vec::new(),
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: None,
+            tcp_keepalive: None,
+            local_address: None,
+            ip_version_preference: None,
+            #[cfg(feature = "gzip")]
+            gzip: true,
+            #[cfg(feature = "brotli")]
+            brotli: true,
+            default_headers: Vec::new(),
+            default_query_params: HashMap::new(),
+            interceptors: Vec::new(),
+            generate_idempotency_keys: false,
+            deserialization_mode: DeserializationMode::default(),
+            check_quote_invariants: false,
+            max_response_body_size: Some(DEFAULT_MAX_RESPONSE_BODY_SIZE),
+            expected_api_version: None,
+            timeouts: EndpointTimeouts::default(),
+            retry_policy: None,
+            rate_limit: None,
+        }
+    }
+
+    pub fn base_path(mut self, base_path: impl Into<String>) -> Self {
+        self.base_path = base_path.into();
+        self
+    }
+
+    /// Host of the other APIs, derived from the base path by default
+    pub fn api_base_path(mut self, api_base_path: impl Into<String>) -> Self {
+        self.api_base_path = Some(api_base_path.into());
+        self
+    }
+
+    /// Layout of the base path, detected from it by default
+    pub fn api_version(mut self, api_version: ApiVersion) -> Self {
+        self.api_version = Some(api_version);
+        self
+    }
+
+    /// Tried in the order added when the previous base path fails with a connection error or a 5xx response
+    pub fn fallback_base_path(mut self, base_path: impl Into<String>) -> Self {
+        self.fallback_base_paths.push(base_path.into());
+        self
+    }
+
+    /// Sent as the [`API_KEY_HEADER`](crate::API_KEY_HEADER) on every request
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Total timeout of a request, from connecting until the response body has been read
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn quote_timeout(mut self, timeout: Duration) -> Self {
+        self.timeouts.quote = Some(timeout);
+        self
+    }
+
+    pub fn swap_timeout(mut self, timeout: Duration) -> Self {
+        self.timeouts.swap = Some(timeout);
+        self
+    }
+
+    pub fn swap_instructions_timeout(mut self, timeout: Duration) -> Self {
+        self.timeouts.swap_instructions = Some(timeout);
+        self
+    }
+
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Limit outgoing requests to `requests_per_second`, allowing bursts of `burst` requests
+    pub fn rate_limit(mut self, requests_per_second: f64, burst: u32) -> Self {
+        self.rate_limit = Some((requests_per_second, burst));
+        self
+    }
+
+    /// Replaces the default [`USER_AGENT`]
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Append an application identifier like `my-bot/1.2.0` to the user agent,
+    /// letting API operators attribute traffic
+    pub fn app_identifier(mut self, app_identifier: impl AsRef<str>) -> Self {
+        self.user_agent.push(' ');
+        self.user_agent.push_str(app_identifier.as_ref());
+        self
+    }
+
+    /// Route requests through a proxy, e.g. `Proxy::all("http://proxy:8080")?.basic_auth(user, password)`
+    ///
+    /// `socks5://` proxies require the `socks` feature.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    /// Maximum number of idle connections kept open per host
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// How long idle connections are kept open, keep it high to avoid reconnecting between quotes
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    pub fn tcp_keepalive(mut self, interval: Duration) -> Self {
+        self.tcp_keepalive = Some(interval);
+        self
+    }
+
+    /// Bind outgoing connections to a local address
+    pub fn local_address(mut self, local_address: IpAddr) -> Self {
+        self.local_address = Some(local_address);
+        self
+    }
+
+    /// Filter or reorder resolved addresses, e.g. to avoid a slow IPv6 route
+    pub fn ip_version_preference(mut self, preference: IpVersionPreference) -> Self {
+        self.ip_version_preference = Some(preference);
+        self
+    }
+
+    /// Negotiate and transparently decompress gzip responses, enabled by default with the `gzip` feature
+    #[cfg(feature = "gzip")]
+    pub fn gzip(mut self, enable: bool) -> Self {
+        self.gzip = enable;
+        self
+    }
+
+    /// Negotiate and transparently decompress brotli responses, enabled by default with the `brotli` feature
+    #[cfg(feature = "brotli")]
+    pub fn brotli(mut self, enable: bool) -> Self {
+        self.brotli = enable;
+        self
+    }
+
+    /// Header attached to every request, validated when building the client
+    pub fn default_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.default_headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Query parameter appended to every request
+    pub fn default_query_param(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.default_query_params.insert(key.into(), value.into());
+        self
+    }
+
+    /// Interceptors run in the order added
+    pub fn interceptor(mut self, interceptor: impl Interceptor + 'static) -> Self {
+        self.interceptors.push(Arc::new(interceptor));
+        self
+    }
+
+    /// Attach a generated idempotency key to POST calls, reused across their retries
+    pub fn generate_idempotency_keys(mut self, enable: bool) -> Self {
+        self.generate_idempotency_keys = enable;
+        self
+    }
+
+    /// Use [`DeserializationMode::Strict`] in CI to detect new response fields
+    pub fn deserialization_mode(mut self, deserialization_mode: DeserializationMode) -> Self {
+        self.deserialization_mode = deserialization_mode;
+        self
+    }
+
+    /// Reject quotes failing [`QuoteResponse::check_invariants`](crate::quote::QuoteResponse::check_invariants)
+    pub fn check_quote_invariants(mut self, enable: bool) -> Self {
+        self.check_quote_invariants = enable;
+        self
+    }
+
+    /// Defaults to [`DEFAULT_MAX_RESPONSE_BODY_SIZE`], `None` reads bodies of any size
+    pub fn max_response_body_size(mut self, max_response_body_size: Option<usize>) -> Self {
+        self.max_response_body_size = max_response_body_size;
+        self
+    }
+
+    /// Send and verify the API version, see [`JupiterSwapApiClient::expected_api_version`]
+    pub fn expected_api_version(mut self, api_version: impl Into<String>) -> Self {
+        self.expected_api_version = Some(api_version.into());
+        self
+    }
+
+    pub fn build(self) -> Result<JupiterSwapApiClient, JupiterError> {
+        let rate_limiter = self
+            .rate_limit
+            .map(|(requests_per_second, burst)| RateLimiter::new(requests_per_second, burst))
+            .transpose()?
+            .map(Arc::new);
+
+        if let Some(api_key) = &self.api_key {
+            HeaderValue::from_str(api_key)
+                .map_err(|e| JupiterError::InvalidConfig(format!("invalid api key: {e}")))?;
+        }
+
+        let mut default_headers = HeaderMap::new();
+        for (name, value) in &self.default_headers {
+            let header_name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| JupiterError::InvalidConfig(format!("invalid header {name}: {e}")))?;
+            let header_value = HeaderValue::from_str(value).map_err(|e| {
+                JupiterError::InvalidConfig(format!("invalid value for header {name}: {e}"))
+            })?;
+            default_headers.append(header_name, header_value);
+        }
+
+        let mut client_builder = Client::builder().user_agent(self.user_agent);
+        if let Some(timeout) = self.timeout {
+            client_builder = client_builder.timeout(timeout);
+        }
+        if let Some(max) = self.pool_max_idle_per_host {
+            client_builder = client_builder.pool_max_idle_per_host(max);
+        }
+        if let Some(timeout) = self.pool_idle_timeout {
+            client_builder = client_builder.pool_idle_timeout(timeout);
+        }
+        if let Some(interval) = self.tcp_keepalive {
+            client_builder = client_builder.tcp_keepalive(interval);
+        }
+        if let Some(local_address) = self.local_address {
+            client_builder = client_builder.local_address(local_address);
+        }
+        if let Some(preference) = self.ip_version_preference {
+            client_builder =
+                client_builder.dns_resolver(Arc::new(IpVersionResolver { preference }));
+        }
+        #[cfg(feature = "gzip")]
+        {
+            client_builder = client_builder.gzip(self.gzip);
+        }
+        #[cfg(feature = "brotli")]
+        {
+            client_builder = client_builder.brotli(self.brotli);
+        }
+        for proxy in self.proxies {
+            client_builder = client_builder.proxy(proxy);
+        }
+        let client = client_builder
+            .build()
+            .map_err(|e| JupiterError::InvalidConfig(e.to_string()))?;
+
+        let mut jupiter_swap_api_client =
+            JupiterSwapApiClient::new_with_client(self.base_path, client);
+        if let Some(api_version) = self.api_version {
+            jupiter_swap_api_client.api_version = api_version;
+        }
+        if let Some(api_base_path) = self.api_base_path {
+            jupiter_swap_api_client.api_base_path = normalize_base_path(api_base_path);
+        }
+        jupiter_swap_api_client.fallback_base_paths = self
+            .fallback_base_paths
+            .into_iter()
+            .map(normalize_base_path)
+            .collect();
+        jupiter_swap_api_client.api_key = self.api_key;
+        jupiter_swap_api_client.default_headers = default_headers;
+        jupiter_swap_api_client.default_query_params = self.default_query_params;
+        jupiter_swap_api_client.interceptors = self.interceptors;
+        jupiter_swap_api_client.generate_idempotency_keys = self.generate_idempotency_keys;
+        jupiter_swap_api_client.deserialization_mode = self.deserialization_mode;
+        jupiter_swap_api_client.check_quote_invariants = self.check_quote_invariants;
+        jupiter_swap_api_client.max_response_body_size = self.max_response_body_size;
+        jupiter_swap_api_client.expected_api_version = self.expected_api_version;
+        jupiter_swap_api_client.timeouts = self.timeouts;
+        jupiter_swap_api_client.retry_policy = self.retry_policy;
+        jupiter_swap_api_client.rate_limiter = rate_limiter;
+        Ok(jupiter_swap_api_client)
+    }
+}
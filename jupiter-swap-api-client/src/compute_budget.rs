@@ -0,0 +1,86 @@
+//! Compute budget instructions of a swap, to enforce fee policies client side
+
+use solana_sdk::{
+    compute_budget::{self, ComputeBudgetInstruction},
+    instruction::Instruction,
+};
+
+use crate::{math, swap::SwapInstructionsResponse};
+
+/// Borsh discriminants of `ComputeBudgetInstruction`
+const SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+const SET_COMPUTE_UNIT_PRICE: u8 = 3;
+
+/// Argument of the compute budget instruction with `discriminant`
+fn compute_budget_arg<const N: usize>(
+    instruction: &Instruction,
+    discriminant: u8,
+) -> Option<[u8; N]> {
+    if instruction.program_id != compute_budget::id() {
+        return None;
+    }
+    match instruction.data.split_first() {
+        Some((first, arg)) if *first == discriminant => arg.try_into().ok(),
+        _ => None,
+    }
+}
+
+impl SwapInstructionsResponse {
+    /// Compute unit limit requested by the compute budget instructions
+    pub fn requested_compute_unit_limit(&self) -> Option<u32> {
+        self.compute_budget_instructions
+            .iter()
+            .find_map(|instruction| compute_budget_arg(instruction, SET_COMPUTE_UNIT_LIMIT))
+            .map(u32::from_le_bytes)
+    }
+
+    /// Compute unit price in micro lamports requested by the compute budget instructions
+    pub fn requested_compute_unit_price(&self) -> Option<u64> {
+        self.compute_budget_instructions
+            .iter()
+            .find_map(|instruction| compute_budget_arg(instruction, SET_COMPUTE_UNIT_PRICE))
+            .map(u64::from_le_bytes)
+    }
+
+    /// Priority fee paid at most, the price of the whole requested compute unit limit
+    pub fn max_priority_fee_lamports(&self) -> Option<u64> {
+        math::mul_div_ceil(
+            self.requested_compute_unit_price()?,
+            u64::from(self.requested_compute_unit_limit()?),
+            1_000_000,
+        )
+    }
+
+    /// Replaces the requested compute unit limit, adding the instruction if missing
+    pub fn set_compute_unit_limit(&mut self, units: u32) {
+        self.replace_compute_budget_instruction(
+            SET_COMPUTE_UNIT_LIMIT,
+            ComputeBudgetInstruction::set_compute_unit_limit(units),
+        );
+        self.compute_unit_limit = units;
+    }
+
+    /// Replaces the requested compute unit price, adding the instruction if missing
+    ///
+    /// `prioritization_fee_lamports` keeps the fee reported by the API, see [`SwapInstructionsResponse::max_priority_fee_lamports`].
+    pub fn set_compute_unit_price(&mut self, micro_lamports: u64) {
+        self.replace_compute_budget_instruction(
+            SET_COMPUTE_UNIT_PRICE,
+            ComputeBudgetInstruction::set_compute_unit_price(micro_lamports),
+        );
+    }
+
+    fn replace_compute_budget_instruction(&mut self, discriminant: u8, instruction: Instruction) {
+        let existing = self
+            .compute_budget_instructions
+            .iter_mut()
+            .find(|existing| {
+                existing.program_id == compute_budget::id()
+                    && existing.data.first() == Some(&discriminant)
+            });
+        match existing {
+            Some(existing) => *existing = instruction,
+            None => self.compute_budget_instructions.push(instruction),
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! Dex labels to restrict or exclude from routing
+
+use std::{convert::Infallible, fmt, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Label of a dex as returned by `/program-id-to-label`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Dex {
+    Whirlpool,
+    RaydiumClmm,
+    RaydiumCp,
+    Raydium,
+    MeteoraDlmm,
+    MeteoraDammV2,
+    Meteora,
+    Phoenix,
+    OpenBookV2,
+    LifinityV2,
+    Obric,
+    SolFi,
+    Sanctum,
+    SanctumInfinity,
+    StabbleStableSwap,
+    StabbleWeightedSwap,
+    PumpFun,
+    PumpFunAmm,
+    Saber,
+    Invariant,
+    Perps,
+    TokenSwap,
+    /// Any label this client doesn't know about yet
+    Other(String),
+}
+
+impl Dex {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Whirlpool => "Whirlpool",
+            Self::RaydiumClmm => "Raydium CLMM",
+            Self::RaydiumCp => "Raydium CP",
+            Self::Raydium => "Raydium",
+            Self::MeteoraDlmm => "Meteora DLMM",
+            Self::MeteoraDammV2 => "Meteora DAMM v2",
+            Self::Meteora => "Meteora",
+            Self::Phoenix => "Phoenix",
+            Self::OpenBookV2 => "OpenBook V2",
+            Self::LifinityV2 => "Lifinity V2",
+            Self::Obric => "Obric V2",
+            Self::SolFi => "SolFi",
+            Self::Sanctum => "Sanctum",
+            Self::SanctumInfinity => "Sanctum Infinity",
+            Self::StabbleStableSwap => "Stabble Stable Swap",
+            Self::StabbleWeightedSwap => "Stabble Weighted Swap",
+            Self::PumpFun => "Pump.fun",
+            Self::PumpFunAmm => "Pump.fun Amm",
+            Self::Saber => "Saber",
+            Self::Invariant => "Invariant",
+            Self::Perps => "Perps",
+            Self::TokenSwap => "Token Swap",
+            Self::Other(label) => label,
+        }
+    }
+}
+
+impl From<&str> for Dex {
+    fn from(label: &str) -> Self {
+        match label {
+            "Whirlpool" => Self::Whirlpool,
+            "Raydium CLMM" => Self::RaydiumClmm,
+            "Raydium CP" => Self::RaydiumCp,
+            "Raydium" => Self::Raydium,
+            "Meteora DLMM" => Self::MeteoraDlmm,
+            "Meteora DAMM v2" => Self::MeteoraDammV2,
+            "Meteora" => Self::Meteora,
+            "Phoenix" => Self::Phoenix,
+            "OpenBook V2" => Self::OpenBookV2,
+            "Lifinity V2" => Self::LifinityV2,
+            "Obric V2" => Self::Obric,
+            "SolFi" => Self::SolFi,
+            "Sanctum" => Self::Sanctum,
+            "Sanctum Infinity" => Self::SanctumInfinity,
+            "Stabble Stable Swap" => Self::StabbleStableSwap,
+            "Stabble Weighted Swap" => Self::StabbleWeightedSwap,
+            "Pump.fun" => Self::PumpFun,
+            "Pump.fun Amm" => Self::PumpFunAmm,
+            "Saber" => Self::Saber,
+            "Invariant" => Self::Invariant,
+            "Perps" => Self::Perps,
+            "Token Swap" => Self::TokenSwap,
+            _ => Self::Other(label.to_string()),
+        }
+    }
+}
+
+impl FromStr for Dex {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from(s.trim()))
+    }
+}
+
+impl fmt::Display for Dex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for Dex {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Dex {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self::from(String::deserialize(deserializer)?.as_str()))
+    }
+}
+
+/// Dexes sent as a comma delimited list of labels
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Dexes(pub Vec<Dex>);
+
+impl Dexes {
+    pub fn iter(&self) -> impl Iterator<Item = &Dex> {
+        self.0.iter()
+    }
+
+    pub fn contains(&self, dex: &Dex) -> bool {
+        self.0.contains(dex)
+    }
+}
+
+impl FromIterator<Dex> for Dexes {
+    fn from_iter<I: IntoIterator<Item = Dex>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<const N: usize> From<[Dex; N]> for Dexes {
+    fn from(dexes: [Dex; N]) -> Self {
+        Self(dexes.into())
+    }
+}
+
+impl From<Vec<Dex>> for Dexes {
+    fn from(dexes: Vec<Dex>) -> Self {
+        Self(dexes)
+    }
+}
+
+impl From<Dex> for Dexes {
+    fn from(dex: Dex) -> Self {
+        Self(vec![dex])
+    }
+}
+
+/// Parses a comma delimited list like `"Whirlpool,Meteora DLMM"`
+impl From<&str> for Dexes {
+    fn from(labels: &str) -> Self {
+        labels
+            .split(',')
+            .map(str::trim)
+            .filter(|label| !label.is_empty())
+            .map(Dex::from)
+            .collect()
+    }
+}
+
+impl From<String> for Dexes {
+    fn from(labels: String) -> Self {
+        Self::from(labels.as_str())
+    }
+}
+
+impl FromStr for Dexes {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from(s))
+    }
+}
+
+impl fmt::Display for Dexes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, dex) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            f.write_str(dex.as_str())?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for Dexes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Dexes {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self::from(String::deserialize(deserializer)?.as_str()))
+    }
+}
@@ -0,0 +1,51 @@
+use std::{io, net::SocketAddr};
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+
+/// Which resolved addresses to connect to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpVersionPreference {
+    Ipv4Only,
+    Ipv6Only,
+    /// Try IPv4 addresses first, falling back to IPv6
+    PreferIpv4,
+    /// Try IPv6 addresses first, falling back to IPv4
+    PreferIpv6,
+}
+
+impl IpVersionPreference {
+    fn apply(self, mut addrs: Vec<SocketAddr>) -> Vec<SocketAddr> {
+        match self {
+            Self::Ipv4Only => addrs.retain(SocketAddr::is_ipv4),
+            Self::Ipv6Only => addrs.retain(SocketAddr::is_ipv6),
+            // Stable sort keeps the resolver order within each family
+            Self::PreferIpv4 => addrs.sort_by_key(SocketAddr::is_ipv6),
+            Self::PreferIpv6 => addrs.sort_by_key(SocketAddr::is_ipv4),
+        }
+        addrs
+    }
+}
+
+/// System resolver filtering or reordering addresses by IP version
+#[derive(Debug, Clone, Copy)]
+pub struct IpVersionResolver {
+    pub preference: IpVersionPreference,
+}
+
+impl Resolve for IpVersionResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let preference = self.preference;
+        Box::pin(async move {
+            let addrs = tokio::net::lookup_host((name.as_str(), 0)).await?.collect();
+            let addrs = preference.apply(addrs);
+            if addrs.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrNotAvailable,
+                    format!("no {preference:?} address found for {}", name.as_str()),
+                )
+                .into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
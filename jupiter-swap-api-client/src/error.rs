@@ -0,0 +1,317 @@
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use solana_sdk::{signature::Signature, transaction::TransactionError};
+use thiserror::Error;
+
+use crate::{quote::QuoteInvariantViolation, request_options::RequestContext, JupiterApiErrorCode};
+
+#[derive(Debug, Error)]
+pub enum JupiterError {
+    /// The request could not be sent or the connection dropped while reading the response,
+    /// e.g. DNS resolution or connecting failed, or the connection was reset
+    #[error("Connection failed: {source}")]
+    Connect {
+        #[source]
+        source: reqwest::Error,
+        request: Option<Box<RequestContext>>,
+    },
+    #[error("Request timed out")]
+    Timeout {
+        #[source]
+        source: reqwest::Error,
+        request: Option<Box<RequestContext>>,
+    },
+    /// Unsuccessful status code without an API error message, or any 5xx response
+    #[error("Request failed with status code {status_code}{}", message.as_ref().map(|m| format!(": {m}")).unwrap_or_default())]
+    Status {
+        status_code: StatusCode,
+        /// `error` or `message` of a JSON body, set on 5xx responses
+        message: Option<String>,
+        /// Raw response body
+        body: String,
+        content_type: Option<String>,
+        request: Option<Box<RequestContext>>,
+    },
+    /// The response body could not be decoded, e.g. decompressed, or isn't valid JSON
+    #[error("Failed to decode response with status code {status_code}: {source}")]
+    Decode {
+        status_code: StatusCode,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+        content_type: Option<String>,
+        request: Option<Box<RequestContext>>,
+    },
+    /// The response body exceeds [`JupiterSwapApiClient::max_response_body_size`](crate::JupiterSwapApiClient::max_response_body_size)
+    #[error("Response body with status code {status_code} exceeds {limit} bytes")]
+    ResponseTooLarge {
+        status_code: StatusCode,
+        limit: usize,
+        request: Option<Box<RequestContext>>,
+    },
+    /// The response reports another version than [`JupiterSwapApiClient::expected_api_version`](crate::JupiterSwapApiClient::expected_api_version)
+    #[error("Expected API version {expected}, got {actual}")]
+    ApiVersionMismatch {
+        expected: String,
+        actual: String,
+        request: Option<Box<RequestContext>>,
+    },
+    /// The response isn't JSON, e.g. an HTML challenge or error page served by a CDN
+    #[error("Non JSON response with status code {status_code} and content type {content_type}: {snippet}")]
+    NonJsonResponse {
+        status_code: StatusCode,
+        content_type: String,
+        /// Start of the body, truncated to [`MAX_RAW_BODY_LEN`] bytes
+        snippet: String,
+        request: Option<Box<RequestContext>>,
+    },
+    #[error("API error: {code} - {msg}")]
+    ApiError {
+        code: JupiterApiErrorCode,
+        msg: String,
+        request: Option<Box<RequestContext>>,
+    },
+    /// The response is valid JSON but doesn't match the expected schema
+    #[error("Failed to deserialize response at {path}: {serde_error}")]
+    Deserialization {
+        /// Truncated to [`MAX_RAW_BODY_LEN`] bytes
+        raw_body: String,
+        #[source]
+        serde_error: serde_json::Error,
+        /// Path of the offending field, e.g. `routePlan[0].swapInfo.feeAmount`
+        path: String,
+        request: Option<Box<RequestContext>>,
+    },
+    /// The response has fields unknown to this client, only in [`DeserializationMode::Strict`](crate::request_options::DeserializationMode::Strict)
+    #[error("Response has unknown fields: {}", fields.join(", "))]
+    UnknownFields {
+        /// Paths of the unknown fields, e.g. `routePlan.0.swapInfo.newField`
+        fields: Vec<String>,
+        request: Option<Box<RequestContext>>,
+    },
+    /// The response has the expected shape but contains invalid values, e.g. a malformed pubkey
+    #[error("Malformed response: {0}")]
+    MalformedResponse(String),
+    /// Only returned when [`JupiterSwapApiClient::check_quote_invariants`](crate::JupiterSwapApiClient::check_quote_invariants) is enabled
+    #[error("Invalid quote: {0}")]
+    InvalidQuote(#[from] QuoteInvariantViolation),
+    #[error("Rate limited, retry after {retry_after:?}")]
+    RateLimited {
+        retry_after: Option<Duration>,
+        request: Option<Box<RequestContext>>,
+    },
+    /// Rejected locally without sending the request
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+    /// Building a transaction failed locally, e.g. compiling its message or serializing it
+    #[error("Transaction error: {0}")]
+    Transaction(String),
+    /// A [`TransactionSigner`](crate::signer::TransactionSigner) failed, e.g. a remote signer is unreachable
+    ///
+    /// Not retried, as signing can require user interaction. Retry remote signer outages at the call site if needed.
+    #[error("Signing failed: {0}")]
+    Signing(String),
+    /// Rejected by RPC nodes, reduce `max_accounts` or enable `use_shared_accounts` and quote again
+    #[error("Transaction of {size} bytes exceeds the {limit} bytes packet limit")]
+    TransactionTooLarge { size: usize, limit: usize },
+    #[error("Transaction {signature} failed: {error}")]
+    TransactionFailed {
+        signature: Signature,
+        error: TransactionError,
+    },
+    /// The block height exceeded the last valid block height before the transaction landed
+    #[error("Transaction {signature} expired before landing")]
+    TransactionExpired { signature: Signature },
+    #[error("Client is shut down")]
+    ShutDown,
+    #[error("Shutdown timed out with {in_flight} requests in flight")]
+    ShutdownTimeout { in_flight: usize },
+    #[error("Invalid client configuration: {0}")]
+    InvalidConfig(String),
+    #[cfg(feature = "solana")]
+    #[error("RPC request failed: {0}")]
+    Rpc(#[from] Box<solana_client::client_error::ClientError>),
+}
+
+#[cfg(feature = "solana")]
+impl From<solana_client::client_error::ClientError> for JupiterError {
+    fn from(error: solana_client::client_error::ClientError) -> Self {
+        JupiterError::Rpc(Box::new(error))
+    }
+}
+
+impl JupiterError {
+    /// Transient failures worth retrying: connection failures, timeouts, rate limits and 5xx responses
+    pub fn is_retryable(&self) -> bool {
+        self.is_connect() || self.is_timeout() || self.is_rate_limited() || self.is_server_error()
+    }
+
+    pub fn is_connect(&self) -> bool {
+        matches!(self, JupiterError::Connect { .. })
+    }
+
+    pub fn is_rate_limited(&self) -> bool {
+        matches!(self, JupiterError::RateLimited { .. })
+    }
+
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, JupiterError::Timeout { .. })
+    }
+
+    pub fn is_server_error(&self) -> bool {
+        match self {
+            JupiterError::Status { status_code, .. }
+            | JupiterError::NonJsonResponse { status_code, .. } => status_code.is_server_error(),
+            _ => false,
+        }
+    }
+
+    /// The request was rejected and will fail again unless it is changed
+    pub fn is_client_error(&self) -> bool {
+        match self {
+            JupiterError::ApiError { .. }
+            | JupiterError::InvalidRequest(_)
+            | JupiterError::TransactionTooLarge { .. }
+            | JupiterError::InvalidConfig(_) => true,
+            JupiterError::Status { status_code, .. }
+            | JupiterError::NonJsonResponse { status_code, .. } => status_code.is_client_error(),
+            _ => false,
+        }
+    }
+
+    /// No route exists for the pair and amount, which can be transient right after a market got listed
+    pub fn is_no_route(&self) -> bool {
+        match self {
+            JupiterError::ApiError { code, msg, .. } => {
+                matches!(
+                    code,
+                    JupiterApiErrorCode::CouldNotFindAnyRoute | JupiterApiErrorCode::NoRoutesFound
+                ) || msg.contains("Could not find any route")
+            }
+            _ => false,
+        }
+    }
+
+    /// Method and URL of the failed request, to reproduce it
+    pub fn request_context(&self) -> Option<&RequestContext> {
+        match self {
+            JupiterError::Connect { request, .. }
+            | JupiterError::Timeout { request, .. }
+            | JupiterError::Status { request, .. }
+            | JupiterError::Decode { request, .. }
+            | JupiterError::NonJsonResponse { request, .. }
+            | JupiterError::ResponseTooLarge { request, .. }
+            | JupiterError::ApiVersionMismatch { request, .. }
+            | JupiterError::ApiError { request, .. }
+            | JupiterError::Deserialization { request, .. }
+            | JupiterError::UnknownFields { request, .. }
+            | JupiterError::RateLimited { request, .. } => request.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn with_request_context(mut self, context: &RequestContext) -> Self {
+        match &mut self {
+            JupiterError::Connect { request, .. }
+            | JupiterError::Timeout { request, .. }
+            | JupiterError::Status { request, .. }
+            | JupiterError::Decode { request, .. }
+            | JupiterError::NonJsonResponse { request, .. }
+            | JupiterError::ResponseTooLarge { request, .. }
+            | JupiterError::ApiVersionMismatch { request, .. }
+            | JupiterError::ApiError { request, .. }
+            | JupiterError::Deserialization { request, .. }
+            | JupiterError::UnknownFields { request, .. }
+            | JupiterError::RateLimited { request, .. } => {
+                *request = Some(Box::new(context.clone()))
+            }
+            _ => {}
+        }
+        self
+    }
+
+    /// Message of an unsuccessful response, falling back to its raw body
+    pub fn server_message(&self) -> Option<&str> {
+        match self {
+            JupiterError::Status { message, body, .. } => message
+                .as_deref()
+                .or_else(|| Some(body.trim()).filter(|body| !body.is_empty())),
+            JupiterError::ApiError { msg, .. } => Some(msg),
+            _ => None,
+        }
+    }
+
+    pub fn status_code(&self) -> Option<StatusCode> {
+        match self {
+            JupiterError::Status { status_code, .. }
+            | JupiterError::Decode { status_code, .. }
+            | JupiterError::NonJsonResponse { status_code, .. }
+            | JupiterError::ResponseTooLarge { status_code, .. } => Some(*status_code),
+            JupiterError::RateLimited { .. } => Some(StatusCode::TOO_MANY_REQUESTS),
+            _ => None,
+        }
+    }
+}
+
+/// Maps a failure to send a request
+///
+/// The URL is dropped from `error` as it may contain secrets, see [`RequestContext`] instead.
+pub(crate) fn send_error(error: reqwest::Error) -> JupiterError {
+    let error = error.without_url();
+    if error.is_timeout() {
+        JupiterError::Timeout {
+            source: error,
+            request: None,
+        }
+    } else if error.is_builder() {
+        JupiterError::InvalidRequest(error.to_string())
+    } else {
+        JupiterError::Connect {
+            source: error,
+            request: None,
+        }
+    }
+}
+
+/// Maps a failure to read the response body
+pub(crate) fn body_error(
+    error: reqwest::Error,
+    status_code: StatusCode,
+    content_type: Option<String>,
+) -> JupiterError {
+    let error = error.without_url();
+    if error.is_timeout() {
+        JupiterError::Timeout {
+            source: error,
+            request: None,
+        }
+    } else if error.is_body() || error.is_request() {
+        // The connection dropped mid-body, retryable unlike an undecodable body
+        JupiterError::Connect {
+            source: error,
+            request: None,
+        }
+    } else {
+        JupiterError::Decode {
+            status_code,
+            source: error.into(),
+            content_type,
+            request: None,
+        }
+    }
+}
+
+/// Longest raw body kept in [`JupiterError::Deserialization`] and [`JupiterError::NonJsonResponse`]
+pub const MAX_RAW_BODY_LEN: usize = 4096;
+
+pub(crate) fn truncated_body(bytes: &[u8]) -> String {
+    let body = String::from_utf8_lossy(bytes);
+    if body.len() <= MAX_RAW_BODY_LEN {
+        return body.into_owned();
+    }
+    let mut end = MAX_RAW_BODY_LEN;
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}...", &body[..end])
+}
@@ -0,0 +1,142 @@
+//! Signing, sending and confirming a swap transaction through an RPC node
+
+use std::time::Duration;
+
+use solana_client::{nonblocking::rpc_client::RpcClient, rpc_config::RpcSendTransactionConfig};
+use solana_sdk::{
+    clock::Slot,
+    commitment_config::{CommitmentConfig, CommitmentLevel},
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::VersionedTransaction,
+};
+
+use crate::{signer::TransactionSigner, swap::SwapResponse, JupiterError};
+
+/// Settings of [`execute_swap`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecuteConfig {
+    /// Commitment the transaction is confirmed at. Default: confirmed
+    pub commitment: CommitmentConfig,
+    /// Skip the simulation of the RPC node before sending. Default: false
+    pub skip_preflight: bool,
+    /// Commitment of the preflight simulation, `None` for the RPC node default
+    pub preflight_commitment: Option<CommitmentLevel>,
+    /// Times the RPC node rebroadcasts the transaction, `None` for the RPC node default
+    pub max_retries: Option<usize>,
+    /// Delay between signature status polls. Default: 500ms
+    pub poll_interval: Duration,
+}
+
+impl Default for ExecuteConfig {
+    fn default() -> Self {
+        Self {
+            commitment: CommitmentConfig::confirmed(),
+            skip_preflight: false,
+            preflight_commitment: None,
+            max_retries: None,
+            poll_interval: Duration::from_millis(500),
+        }
+    }
+}
+
+/// A swap transaction confirmed at [`ExecuteConfig::commitment`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutedSwap {
+    pub signature: Signature,
+    pub slot: Slot,
+}
+
+/// Sets the signature of `signer` in `transaction`, failing if it isn't a required signer
+pub(crate) fn set_signature(
+    transaction: &mut VersionedTransaction,
+    signer: &Pubkey,
+    signature: Signature,
+) -> Result<(), JupiterError> {
+    let num_required_signatures = usize::from(transaction.message.header().num_required_signatures);
+    let index = transaction
+        .message
+        .static_account_keys()
+        .iter()
+        .take(num_required_signatures)
+        .position(|key| key == signer)
+        .ok_or_else(|| {
+            JupiterError::Signing(format!("{signer} is not a signer of the transaction"))
+        })?;
+    transaction
+        .signatures
+        .resize(num_required_signatures, Signature::default());
+    transaction.signatures[index] = signature;
+    Ok(())
+}
+
+/// Sends `transaction` and polls its status until confirmed, failed or expired
+pub(crate) async fn send_and_confirm(
+    transaction: &VersionedTransaction,
+    last_valid_block_height: u64,
+    rpc_client: &RpcClient,
+    config: &ExecuteConfig,
+) -> Result<ExecutedSwap, JupiterError> {
+    let signature = rpc_client
+        .send_transaction_with_config(
+            transaction,
+            RpcSendTransactionConfig {
+                skip_preflight: config.skip_preflight,
+                preflight_commitment: config.preflight_commitment,
+                max_retries: config.max_retries,
+                ..RpcSendTransactionConfig::default()
+            },
+        )
+        .await?;
+    loop {
+        let status = rpc_client
+            .get_signature_statuses(&[signature])
+            .await?
+            .value
+            .into_iter()
+            .next()
+            .flatten();
+        match status {
+            Some(status) => {
+                if let Some(error) = status.err {
+                    return Err(JupiterError::TransactionFailed { signature, error });
+                }
+                if status.satisfies_commitment(config.commitment) {
+                    return Ok(ExecutedSwap {
+                        signature,
+                        slot: status.slot,
+                    });
+                }
+            }
+            // Landed transactions have a status, so the block height only matters without one
+            None => {
+                if rpc_client.get_block_height().await? > last_valid_block_height {
+                    return Err(JupiterError::TransactionExpired { signature });
+                }
+            }
+        }
+        tokio::time::sleep(config.poll_interval).await;
+    }
+}
+
+/// Signs the transaction of `swap_response` with `signer`, a keypair or a remote signer, sends it and waits for its confirmation
+///
+/// Fails with [`JupiterError::TransactionFailed`] if it lands with an error, and with
+/// [`JupiterError::TransactionExpired`] once the block height exceeds [`SwapResponse::last_valid_block_height`].
+pub async fn execute_swap<S: TransactionSigner + ?Sized>(
+    swap_response: &SwapResponse,
+    signer: &S,
+    rpc_client: &RpcClient,
+    config: ExecuteConfig,
+) -> Result<ExecutedSwap, JupiterError> {
+    let mut transaction = swap_response.versioned_transaction()?;
+    let signature = signer.sign(&transaction.message.serialize()).await?;
+    set_signature(&mut transaction, &signer.public_key(), signature)?;
+    send_and_confirm(
+        &transaction,
+        swap_response.last_valid_block_height,
+        rpc_client,
+        &config,
+    )
+    .await
+}
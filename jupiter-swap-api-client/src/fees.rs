@@ -0,0 +1,72 @@
+//! Fees paid by a swap transaction, for fee accounting
+
+use crate::swap::{PrioritizationType, SwapInstructionsResponse, SwapResponse};
+
+/// Base fee of every signature of a transaction
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Fees on top of the base fee, split by how the priority was bought
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FeeBreakdown {
+    /// Paid through the compute unit price
+    pub priority_fee_lamports: u64,
+    /// Paid as a tip to the Jito validator
+    pub jito_tip_lamports: u64,
+    /// Compute unit price used, in micro lamports
+    pub compute_unit_price_micro_lamports: Option<u64>,
+    /// Compute unit price estimated before capping, in micro lamports
+    pub estimated_compute_unit_price_micro_lamports: Option<u64>,
+}
+
+impl FeeBreakdown {
+    fn new(
+        prioritization_fee_lamports: u64,
+        prioritization_type: Option<&PrioritizationType>,
+    ) -> Self {
+        match prioritization_type {
+            Some(PrioritizationType::Jito { lamports }) => Self {
+                jito_tip_lamports: *lamports,
+                ..Self::default()
+            },
+            Some(PrioritizationType::ComputeBudget {
+                micro_lamports,
+                estimated_micro_lamports,
+            }) => Self {
+                priority_fee_lamports: prioritization_fee_lamports,
+                compute_unit_price_micro_lamports: Some(*micro_lamports),
+                estimated_compute_unit_price_micro_lamports: *estimated_micro_lamports,
+                ..Self::default()
+            },
+            None => Self {
+                priority_fee_lamports: prioritization_fee_lamports,
+                ..Self::default()
+            },
+        }
+    }
+
+    /// Everything the fee payer spends, including the base fee of `signatures`
+    pub fn total_lamports(&self, signatures: u64) -> u64 {
+        signatures
+            .saturating_mul(LAMPORTS_PER_SIGNATURE)
+            .saturating_add(self.priority_fee_lamports)
+            .saturating_add(self.jito_tip_lamports)
+    }
+}
+
+impl SwapResponse {
+    pub fn fee_breakdown(&self) -> FeeBreakdown {
+        FeeBreakdown::new(
+            self.prioritization_fee_lamports,
+            self.prioritization_type.as_ref(),
+        )
+    }
+}
+
+impl SwapInstructionsResponse {
+    pub fn fee_breakdown(&self) -> FeeBreakdown {
+        FeeBreakdown::new(
+            self.prioritization_fee_lamports,
+            self.prioritization_type.as_ref(),
+        )
+    }
+}
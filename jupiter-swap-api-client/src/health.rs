@@ -0,0 +1,62 @@
+//! Readiness of a self-hosted swap API
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    request_options::{Endpoint, RequestOptions},
+    JupiterError, JupiterSwapApiClient,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Health {
+    /// e.g. `ok`, the raw body when the API answers with plain text
+    pub status: Option<String>,
+    /// Last slot processed by the market cache
+    #[serde(alias = "lastProcessedSlot")]
+    pub slot: Option<u64>,
+    /// Number of markets loaded
+    #[serde(alias = "marketsCount")]
+    pub market_count: Option<u64>,
+    /// Fields without a typed field yet
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl Health {
+    /// The API processed a slot at most `max_slot_lag` slots behind `current_slot`
+    ///
+    /// False when the API doesn't report its slot.
+    pub fn is_fresh(&self, current_slot: u64, max_slot_lag: u64) -> bool {
+        self.slot
+            .is_some_and(|slot| current_slot.saturating_sub(slot) <= max_slot_lag)
+    }
+}
+
+impl JupiterSwapApiClient {
+    /// Only served by the [`ApiVersion::V6`](crate::api_version::ApiVersion::V6) layout, e.g. self-hosted
+    pub async fn health(&self) -> Result<Health, JupiterError> {
+        let path = self.swap_api_path(Endpoint::Health)?;
+        let response = self
+            .send::<Health>(
+                Endpoint::Health,
+                |base_path| self.client.get(format!("{base_path}{path}")),
+                &RequestOptions::default(),
+            )
+            .await;
+        match response {
+            Ok((health, _)) => Ok(health),
+            Err(JupiterError::NonJsonResponse {
+                status_code,
+                snippet,
+                ..
+            }) if status_code.is_success() => Ok(Health {
+                status: Some(snippet),
+                ..Health::default()
+            }),
+            Err(e) => Err(e),
+        }
+    }
+}
@@ -0,0 +1,139 @@
+//! Classification of setup and cleanup instructions, to drop the ones already handled by the integrator
+
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey, system_program};
+
+use crate::{
+    swap::SwapInstructionsResponse,
+    token_account::{ASSOCIATED_TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID},
+};
+
+/// `SystemInstruction::Transfer`, bincode encoded as a u32
+const SYSTEM_TRANSFER: [u8; 4] = 2u32.to_le_bytes();
+const TOKEN_CLOSE_ACCOUNT: u8 = 9;
+const TOKEN_SYNC_NATIVE: u8 = 17;
+
+/// What a setup or cleanup instruction does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstructionKind {
+    /// Creates the associated token account of `owner` for `mint`, idempotent or not
+    CreateAssociatedTokenAccount {
+        token_account: Pubkey,
+        owner: Pubkey,
+        mint: Pubkey,
+    },
+    /// Transfers lamports into `token_account` ahead of syncing it, to wrap SOL
+    TransferLamports {
+        token_account: Pubkey,
+    },
+    /// Syncs the wrapped SOL balance of `token_account` with its lamports
+    SyncNative {
+        token_account: Pubkey,
+    },
+    /// Closes `token_account`, unwrapping SOL for wrapped SOL accounts
+    CloseAccount {
+        token_account: Pubkey,
+    },
+    Other,
+}
+
+fn is_token_program(program_id: &Pubkey) -> bool {
+    *program_id == TOKEN_PROGRAM_ID || *program_id == TOKEN_2022_PROGRAM_ID
+}
+
+impl InstructionKind {
+    pub fn of(instruction: &Instruction) -> Self {
+        let account = |index: usize| instruction.accounts.get(index).map(|meta| meta.pubkey);
+        let kind = if instruction.program_id == ASSOCIATED_TOKEN_PROGRAM_ID
+            && matches!(instruction.data.as_slice(), [] | [0] | [1])
+        {
+            account(1)
+                .zip(account(2))
+                .zip(account(3))
+                .map(
+                    |((token_account, owner), mint)| Self::CreateAssociatedTokenAccount {
+                        token_account,
+                        owner,
+                        mint,
+                    },
+                )
+        } else if instruction.program_id == system_program::id()
+            && instruction.data.starts_with(&SYSTEM_TRANSFER)
+        {
+            account(1).map(|token_account| Self::TransferLamports { token_account })
+        } else if is_token_program(&instruction.program_id) {
+            match instruction.data.first() {
+                Some(&TOKEN_SYNC_NATIVE) => {
+                    account(0).map(|token_account| Self::SyncNative { token_account })
+                }
+                Some(&TOKEN_CLOSE_ACCOUNT) => {
+                    account(0).map(|token_account| Self::CloseAccount { token_account })
+                }
+                _ => None,
+            }
+        } else {
+            None
+        };
+        kind.unwrap_or(Self::Other)
+    }
+
+    /// Part of wrapping SOL into a token account
+    pub fn is_wrap_sol(&self) -> bool {
+        matches!(
+            self,
+            Self::TransferLamports { .. } | Self::SyncNative { .. }
+        )
+    }
+}
+
+impl SwapInstructionsResponse {
+    pub fn setup_instruction_kinds(&self) -> Vec<InstructionKind> {
+        self.setup_instructions
+            .iter()
+            .map(InstructionKind::of)
+            .collect()
+    }
+
+    pub fn cleanup_instruction_kind(&self) -> Option<InstructionKind> {
+        self.cleanup_instruction.as_ref().map(InstructionKind::of)
+    }
+
+    /// Keeps the setup instructions for which `keep` returns true, e.g. to drop token account creations
+    pub fn retain_setup_instructions(&mut self, mut keep: impl FnMut(&InstructionKind) -> bool) {
+        self.setup_instructions
+            .retain(|instruction| keep(&InstructionKind::of(instruction)));
+    }
+
+    /// Drops the creation of the token accounts of `owner` for `mints`, when already created in the transaction
+    pub fn drop_token_account_creations(&mut self, owner: &Pubkey, mints: &[Pubkey]) {
+        self.retain_setup_instructions(|kind| {
+            !matches!(
+                kind,
+                InstructionKind::CreateAssociatedTokenAccount { owner: created_owner, mint, .. }
+                    if created_owner == owner && mints.contains(mint)
+            )
+        });
+    }
+
+    /// Drops wrapping SOL into `token_account`, when the integrator funds it
+    pub fn drop_wrap_sol(&mut self, token_account: &Pubkey) {
+        self.retain_setup_instructions(|kind| match kind {
+            InstructionKind::TransferLamports {
+                token_account: wrapped,
+            }
+            | InstructionKind::SyncNative {
+                token_account: wrapped,
+            } => wrapped != token_account,
+            _ => true,
+        });
+    }
+
+    /// Drops the cleanup instruction when it closes a token account, to keep the account open
+    pub fn drop_close_account(&mut self) {
+        if matches!(
+            self.cleanup_instruction_kind(),
+            Some(InstructionKind::CloseAccount { .. })
+        ) {
+            self.cleanup_instruction = None;
+        }
+    }
+}
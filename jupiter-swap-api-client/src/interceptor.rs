@@ -0,0 +1,12 @@
+use reqwest::{Request, Response};
+
+/// Hooks run around every HTTP attempt, including retries and failovers
+///
+/// Useful for request signing, telemetry or header mutation without forking the crate.
+pub trait Interceptor: Send + Sync {
+    /// Called with the final request, after the client headers and query parameters were attached
+    fn on_request(&self, _request: &mut Request) {}
+
+    /// Called as soon as the response headers arrived, before the body is read
+    fn on_response(&self, _response: &Response) {}
+}
@@ -0,0 +1,50 @@
+//! Quotes for several sizes of the same swap, to size orders against the available liquidity
+
+use futures_util::future::join_all;
+use rust_decimal::Decimal;
+
+use crate::{
+    quote::{QuoteRequest, QuoteResponse},
+    JupiterError, JupiterSwapApiClient,
+};
+
+/// Quote of one size of a [`JupiterSwapApiClient::quote_ladder`]
+#[derive(Debug)]
+pub struct LadderRung {
+    pub amount: u64,
+    /// Large sizes fail with no route once the liquidity is exhausted
+    pub quote: Result<QuoteResponse, JupiterError>,
+}
+
+impl LadderRung {
+    pub fn out_amount(&self) -> Option<u64> {
+        self.quote.as_ref().ok().map(|quote| quote.out_amount)
+    }
+
+    pub fn price_impact(&self) -> Option<Decimal> {
+        self.quote.as_ref().ok().map(|quote| quote.price_impact_pct)
+    }
+}
+
+impl JupiterSwapApiClient {
+    /// Quotes `quote_request` for every amount concurrently, in the order of `amounts`
+    ///
+    /// Requests wait for the client rate limiter when one is configured, so large ladders are throttled rather than rate limited.
+    pub async fn quote_ladder(
+        &self,
+        quote_request: &QuoteRequest,
+        amounts: &[u64],
+    ) -> Vec<LadderRung> {
+        let quotes = amounts.iter().map(|amount| async move {
+            let quote_request = QuoteRequest {
+                amount: *amount,
+                ..quote_request.clone()
+            };
+            LadderRung {
+                amount: *amount,
+                quote: self.quote(&quote_request).await,
+            }
+        });
+        join_all(quotes).await
+    }
+}
@@ -0,0 +1,128 @@
+//! Lend API v1, earn deposits and withdrawals
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    request_options::{Endpoint, RequestOptions},
+    serde_helpers::field_as_string,
+    swap::base64_serialize_deserialize,
+    JupiterError, JupiterSwapApiClient,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EarnRequest {
+    /// Mint of the underlying asset, e.g. USDC
+    #[serde(with = "field_as_string")]
+    pub asset: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub signer: Pubkey,
+    /// In atomic units of the asset
+    #[serde(with = "field_as_string")]
+    pub amount: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LendTransactionResponse {
+    /// Unsigned versioned transaction, to be signed by the signer
+    #[serde(with = "base64_serialize_deserialize")]
+    pub transaction: Vec<u8>,
+}
+
+/// Receipt token of an earn vault
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EarnToken {
+    #[serde(with = "field_as_string")]
+    pub address: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    #[serde(with = "field_as_string")]
+    pub asset_address: Pubkey,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EarnPosition {
+    pub token: EarnToken,
+    #[serde(with = "field_as_string")]
+    pub owner_address: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub shares: u64,
+    /// Withdrawable amount of the asset, in atomic units
+    #[serde(with = "field_as_string")]
+    pub underlying_assets: u64,
+    /// Asset balance of the owner's wallet, in atomic units
+    #[serde(with = "field_as_string")]
+    pub underlying_balance: u64,
+}
+
+impl JupiterSwapApiClient {
+    /// Build the transaction depositing into the earn vault of `asset`
+    pub async fn lend_deposit(
+        &self,
+        earn_request: &EarnRequest,
+    ) -> Result<LendTransactionResponse, JupiterError> {
+        self.send_earn("deposit", earn_request).await
+    }
+
+    /// Build the transaction withdrawing from the earn vault of `asset`
+    pub async fn lend_withdraw(
+        &self,
+        earn_request: &EarnRequest,
+    ) -> Result<LendTransactionResponse, JupiterError> {
+        self.send_earn("withdraw", earn_request).await
+    }
+
+    /// Earn positions of `users`
+    pub async fn lend_positions(
+        &self,
+        users: &[Pubkey],
+    ) -> Result<Vec<EarnPosition>, JupiterError> {
+        if users.is_empty() {
+            return Ok(Vec::new());
+        }
+        let users = users
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        self.send(
+            Endpoint::LendPositions,
+            |api_base_path| {
+                self.client
+                    .get(format!("{api_base_path}/lend/v1/earn/positions"))
+                    .query(&[("users", &users)])
+            },
+            &RequestOptions::default(),
+        )
+        .await
+        .map(|(positions, _)| positions)
+    }
+
+    async fn send_earn(
+        &self,
+        path: &str,
+        earn_request: &EarnRequest,
+    ) -> Result<LendTransactionResponse, JupiterError> {
+        if earn_request.amount == 0 {
+            return Err(JupiterError::InvalidRequest(
+                "amount has to be greater than 0".to_string(),
+            ));
+        }
+        self.send(
+            Endpoint::LendEarn,
+            |api_base_path| {
+                self.client
+                    .post(format!("{api_base_path}/lend/v1/earn/{path}"))
+                    .json(earn_request)
+            },
+            &RequestOptions::default(),
+        )
+        .await
+        .map(|(lend_transaction_response, _)| lend_transaction_response)
+    }
+}
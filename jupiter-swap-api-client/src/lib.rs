@@ -1,140 +1,869 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
+};
 
-use anyhow::anyhow;
-use quote::{InternalQuoteRequest, QuoteRequest, QuoteResponse};
-use reqwest::{Client, Response};
+pub use api_error_code::JupiterApiErrorCode;
+use api_version::ApiVersion;
+pub use builder::JupiterSwapApiClientBuilder;
+use error::{body_error, send_error, truncated_body};
+pub use error::{JupiterError, MAX_RAW_BODY_LEN};
+use interceptor::Interceptor;
+use quote::{QuoteRequest, QuoteResponse};
+use rate_limit::RateLimiter;
+use request_options::{
+    generate_idempotency_key, DeserializationMode, Endpoint, EndpointTimeouts, RequestContext,
+    RequestOptions, IDEMPOTENCY_KEY_HEADER,
+};
+use reqwest::{header::HeaderMap, Client, RequestBuilder, Response};
+use response_meta::ResponseMeta;
+use retry::RetryPolicy;
 use serde::de::DeserializeOwned;
-use swap::{SwapInstructionsResponse, SwapInstructionsResponseInternal, SwapRequest, SwapResponse};
-use thiserror::Error;
+use shutdown::InFlight;
+use solana_sdk::pubkey::Pubkey;
+use swap::{
+    parse_pubkey, SwapInstructionsResponse, SwapInstructionsResponseInternal, SwapRequest,
+    SwapResponse,
+};
 
+pub mod accounts;
+pub mod amount;
+pub mod api_error_code;
+pub mod api_version;
+pub mod builder;
+pub mod compute_budget;
+pub mod dex;
+pub mod dns;
+pub mod error;
+#[cfg(feature = "solana")]
+pub mod execute;
+pub mod fees;
+pub mod health;
+pub mod instruction_kind;
+pub mod interceptor;
+pub mod ladder;
+#[cfg(feature = "lend")]
+pub mod lend;
+pub mod lookup_tables;
+pub mod markets;
+pub mod math;
+pub mod price;
 pub mod quote;
+pub mod quote_compare;
+pub mod rate_limit;
+pub mod recurring;
+pub mod referral;
+pub mod refresh;
+pub mod request_options;
+pub mod response_meta;
+pub mod retry;
+pub mod route_map;
 pub mod route_plan_with_metadata;
 pub mod serde_helpers;
+mod shutdown;
+pub mod signer;
+#[cfg(feature = "solana")]
+pub mod simulate;
+pub mod slippage;
 pub mod swap;
+pub mod token_account;
+pub mod tokens;
+pub mod transaction;
 pub mod transaction_config;
+pub mod trigger;
+pub mod ultra;
+
+/// Called with the base path that successfully served a request, see [`JupiterSwapApiClient::fallback_base_paths`]
+pub type OnServed = Arc<dyn Fn(Endpoint, &str) + Send + Sync>;
+
+/// Called with every failed attempt, see [`JupiterSwapApiClient::on_error`]
+pub type OnError = Arc<dyn Fn(&JupiterError, &RequestContext) + Send + Sync>;
+
+/// Free, rate limited swap API
+pub const LITE_SWAP_API_URL: &str = "https://lite-api.jup.ag/swap/v1";
+/// Swap API for paid plans, requires an API key
+pub const PRO_SWAP_API_URL: &str = "https://api.jup.ag/swap/v1";
+
+/// Free, rate limited host of the other APIs, e.g. price and tokens
+pub const LITE_API_URL: &str = "https://lite-api.jup.ag";
+/// Host of the other APIs for paid plans, requires an API key
+pub const PRO_API_URL: &str = "https://api.jup.ag";
+
+/// Default `User-Agent`, e.g. `jupiter-swap-api-client/0.1.0`
+pub const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// Default [`JupiterSwapApiClient::max_response_body_size`], far above any legitimate response
+pub const DEFAULT_MAX_RESPONSE_BODY_SIZE: usize = 10 * 1024 * 1024;
+
+/// Header carrying [`JupiterSwapApiClient::expected_api_version`], also read from responses
+pub const API_VERSION_HEADER: &str = "x-api-version";
+
+/// Header carrying the API key required by the paid `api.jup.ag` endpoints
+pub const API_KEY_HEADER: &str = "x-api-key";
 
 #[derive(Clone)]
 pub struct JupiterSwapApiClient {
     pub base_path: String,
+    /// Layout of the base path, detected from it by default
+    pub api_version: ApiVersion,
+    /// Serves every API but the swap API, e.g. [`LITE_API_URL`]
+    pub api_base_path: String,
+    /// Tried in order when the base path fails with a connection error or a 5xx response
+    pub fallback_base_paths: Vec<String>,
+    pub on_served: Option<OnServed>,
+    /// Reports failures centrally, e.g. to Sentry or Prometheus, including the ones retried
+    pub on_error: Option<OnError>,
     pub client: Client,
+    /// Sent as the [`API_KEY_HEADER`] on every request
+    pub api_key: Option<String>,
+    /// Headers attached to every request
+    pub default_headers: HeaderMap,
+    /// Query parameters appended to every request
+    pub default_query_params: HashMap<String, String>,
+    pub timeouts: EndpointTimeouts,
+    /// Retries are disabled when not set
+    pub retry_policy: Option<RetryPolicy>,
+    /// Shared by all clones of the client
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    /// Run in order around every HTTP attempt
+    pub interceptors: Vec<Arc<dyn Interceptor>>,
+    /// Generate an idempotency key for POST calls that don't set [`RequestOptions::idempotency_key`]
+    pub generate_idempotency_keys: bool,
+    pub deserialization_mode: DeserializationMode,
+    /// Reject quotes failing [`QuoteResponse::check_invariants`], e.g. from a self-hosted API
+    pub check_quote_invariants: bool,
+    /// Responses with a larger body fail with [`JupiterError::ResponseTooLarge`], `None` reads any size
+    ///
+    /// Raised per endpoint to [`Endpoint::min_response_body_size_limit`], e.g. for the full token list.
+    pub max_response_body_size: Option<usize>,
+    /// Sent as the [`API_VERSION_HEADER`], responses reporting another version fail with [`JupiterError::ApiVersionMismatch`]
+    pub expected_api_version: Option<String>,
+    in_flight: Arc<InFlight>,
+}
+
+/// Strip trailing slashes so joining paths never produces `//quote`
+pub(crate) fn normalize_base_path(base_path: String) -> String {
+    base_path.trim_end_matches('/').to_string()
 }
 
-#[derive(Debug, Error)]
-pub enum JupiterError {
-    #[error("Request failed with status code {status_code}: {msg}")]
-    RequestFailed {
-        status_code: reqwest::StatusCode,
-        msg: String,
-    },
-    #[error("API error: {code} - {msg}")]
-    ApiError { code: String, msg: String },
+/// The host of a `/swap/v1` base path, [`LITE_API_URL`] for any other layout, e.g. self-hosted
+fn api_base_path(base_path: &str) -> String {
+    base_path
+        .strip_suffix("/swap/v1")
+        .unwrap_or(LITE_API_URL)
+        .to_string()
+}
+
+fn should_fail_over(error: &JupiterError) -> bool {
+    error.is_connect() || error.is_server_error()
+}
+
+/// Reads the body chunk by chunk so that an oversized one is never fully buffered
+async fn read_body(
+    mut response: Response,
+    max_size: Option<usize>,
+    content_type: Option<String>,
+) -> Result<Vec<u8>, JupiterError> {
+    let status = response.status();
+    let too_large = |limit| JupiterError::ResponseTooLarge {
+        status_code: status,
+        limit,
+        request: None,
+    };
+    if let Some(limit) = max_size {
+        if response
+            .content_length()
+            .is_some_and(|content_length| content_length > limit as u64)
+        {
+            return Err(too_large(limit));
+        }
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| body_error(e, status, content_type.clone()))?
+    {
+        if let Some(limit) = max_size.filter(|limit| body.len() + chunk.len() > *limit) {
+            return Err(too_large(limit));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
 }
 
 async fn check_status_code_and_deserialize<T: DeserializeOwned>(
     response: Response,
+    endpoint: Endpoint,
+    deserialization_mode: DeserializationMode,
+    max_response_body_size: Option<usize>,
+    expected_api_version: Option<&str>,
 ) -> Result<T, JupiterError> {
     let status = response.status();
-    let bytes = response
-        .bytes()
-        .await
-        .map_err(|e| JupiterError::RequestFailed {
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(JupiterError::RateLimited {
+            retry_after: retry::retry_after(response.headers(), SystemTime::now()),
+            request: None,
+        });
+    }
+    let header_api_version = response
+        .headers()
+        .get(API_VERSION_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(ToString::to_string);
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(ToString::to_string);
+    let bytes = read_body(response, max_response_body_size, content_type.clone()).await?;
+
+    // Endpoints acknowledging with an empty body, e.g. `/markets`
+    if status.is_success() && bytes.is_empty() {
+        return serde_json::from_value(serde_json::Value::Null).map_err(|serde_error| {
+            JupiterError::Deserialization {
+                raw_body: String::new(),
+                serde_error,
+                path: String::new(),
+                request: None,
+            }
+        });
+    }
+
+    if let Some(content_type) = content_type.as_deref().filter(|c| !is_json(c)) {
+        return Err(JupiterError::NonJsonResponse {
             status_code: status,
-            msg: e.to_string(),
-        })?;
-
-    // if !status.is_success() {
-    //     let msg = String::from_utf8_lossy(&bytes).to_string();
-    //     return Err(JupiterError::RequestFailed {
-    //         status_code: status,
-    //         msg,
-    //     });
-    // }
-
-    let json_value: serde_json::Value =
-        serde_json::from_slice(&bytes).map_err(|e| JupiterError::RequestFailed {
+            content_type: content_type.to_string(),
+            snippet: truncated_body(&bytes).trim().to_string(),
+            request: None,
+        });
+    }
+
+    let json_value = serde_json::from_slice::<serde_json::Value>(&bytes);
+
+    if status.is_server_error() {
+        // Kept as a transient status error so that it's retried, even when the body has an API error
+        return Err(JupiterError::Status {
             status_code: status,
-            msg: e.to_string(),
-        })?;
-
-    if let Some(error_msg) = json_value.get("error").and_then(|v| v.as_str()) {
-        let error_code = json_value
-            .get("errorCode")
-            .map(|v| v.to_string()) // 不论其原始类型，将其转成字符串
-            .unwrap_or_default();
-
-        return Err(JupiterError::ApiError {
-            code: error_code,
-            msg: error_msg.to_string(),
+            message: json_value.ok().as_ref().and_then(server_error_message),
+            body: String::from_utf8_lossy(&bytes).into_owned(),
+            content_type,
+            request: None,
         });
     }
 
-    serde_json::from_value(json_value).map_err(|e| JupiterError::RequestFailed {
+    if !status.is_success() {
+        // Prefer the API error message when the body has one
+        return Err(json_value
+            .ok()
+            .and_then(|json_value| api_error(&json_value))
+            .unwrap_or_else(|| JupiterError::Status {
+                status_code: status,
+                message: None,
+                body: String::from_utf8_lossy(&bytes).into_owned(),
+                content_type,
+                request: None,
+            }));
+    }
+
+    let json_value = json_value.map_err(|e| JupiterError::Decode {
         status_code: status,
-        msg: e.to_string(),
+        source: e.into(),
+        content_type,
+        request: None,
+    })?;
+
+    if !endpoint.reports_errors_in_response() {
+        if let Some(api_error) = api_error(&json_value) {
+            return Err(api_error);
+        }
+    }
+
+    if let Some(expected) = expected_api_version {
+        // Only checked when the response reports a version
+        let actual = header_api_version.or_else(|| {
+            ["apiVersion", "version"]
+                .iter()
+                .find_map(|key| json_value.get(*key)?.as_str().map(ToString::to_string))
+        });
+        if let Some(actual) = actual.filter(|actual| actual != expected) {
+            return Err(JupiterError::ApiVersionMismatch {
+                expected: expected.to_string(),
+                actual,
+                request: None,
+            });
+        }
+    }
+
+    let mut track = serde_path_to_error::Track::new();
+    let mut unknown_fields = Vec::new();
+    let deserializer = serde_path_to_error::Deserializer::new(json_value, &mut track);
+    let result = match deserialization_mode {
+        DeserializationMode::Lenient => T::deserialize(deserializer),
+        DeserializationMode::Strict => {
+            serde_ignored::deserialize(deserializer, |path| unknown_fields.push(path.to_string()))
+        }
+    };
+    let value = result.map_err(|serde_error| JupiterError::Deserialization {
+        raw_body: truncated_body(&bytes),
+        path: track.path().to_string(),
+        serde_error,
+        request: None,
+    })?;
+    if !unknown_fields.is_empty() {
+        return Err(JupiterError::UnknownFields {
+            fields: unknown_fields,
+            request: None,
+        });
+    }
+    Ok(value)
+}
+
+/// `application/json`, or any `+json` suffixed or charset qualified variant
+fn is_json(content_type: &str) -> bool {
+    content_type
+        .split(';')
+        .next()
+        .is_some_and(|mime| mime.trim().to_ascii_lowercase().ends_with("json"))
+}
+
+fn server_error_message(json_value: &serde_json::Value) -> Option<String> {
+    ["error", "message"]
+        .iter()
+        .find_map(|key| json_value.get(*key)?.as_str())
+        .map(ToString::to_string)
+}
+
+fn api_error(json_value: &serde_json::Value) -> Option<JupiterError> {
+    let error_msg = json_value.get("error").and_then(|v| v.as_str())?;
+
+    Some(JupiterError::ApiError {
+        code: api_error_code(json_value),
+        msg: error_msg.to_string(),
+        request: None,
     })
 }
 
+/// Builds return `errorCode` as either a string or a number, newer APIs name it `code`
+fn api_error_code(json_value: &serde_json::Value) -> JupiterApiErrorCode {
+    let code = json_value
+        .get("errorCode")
+        .filter(|code| !code.is_null())
+        .or_else(|| json_value.get("code"));
+    match code {
+        Some(serde_json::Value::String(code)) => code.as_str().into(),
+        Some(serde_json::Value::Number(code)) => code.to_string().as_str().into(),
+        _ => JupiterApiErrorCode::Unknown(String::new()),
+    }
+}
+
+impl Default for JupiterSwapApiClient {
+    fn default() -> Self {
+        Self::lite()
+    }
+}
+
 impl JupiterSwapApiClient {
     pub fn new(base_path: String) -> Self {
+        // Same fallback as `Client::new()`, which panics if the TLS backend cannot be initialized
+        let client = Client::builder()
+            .user_agent(USER_AGENT)
+            .build()
+            .unwrap_or_else(|_| Client::new());
+        Self::new_with_client(base_path, client)
+    }
+
+    /// Client for the free [`LITE_SWAP_API_URL`]
+    pub fn lite() -> Self {
+        Self::new(LITE_SWAP_API_URL.into())
+    }
+
+    /// Client for the paid [`PRO_SWAP_API_URL`]
+    pub fn pro(api_key: impl Into<String>) -> Self {
+        Self::new(PRO_SWAP_API_URL.into()).with_api_key(api_key)
+    }
+
+    /// Use a pre-configured `reqwest::Client`, e.g. with custom timeouts, proxies or TLS settings
+    pub fn new_with_client(base_path: String, client: Client) -> Self {
+        let base_path = normalize_base_path(base_path);
         Self {
+            api_version: ApiVersion::from_base_path(&base_path),
+            api_base_path: api_base_path(&base_path),
             base_path,
-            client: Client::new(),
+            fallback_base_paths: Vec::new(),
+            on_served: None,
+            on_error: None,
+            client,
+            api_key: None,
+            default_headers: HeaderMap::new(),
+            default_query_params: HashMap::new(),
+            timeouts: EndpointTimeouts::default(),
+            retry_policy: None,
+            rate_limiter: None,
+            interceptors: Vec::new(),
+            generate_idempotency_keys: false,
+            deserialization_mode: DeserializationMode::default(),
+            check_quote_invariants: false,
+            max_response_body_size: Some(DEFAULT_MAX_RESPONSE_BODY_SIZE),
+            expected_api_version: None,
+            in_flight: Arc::default(),
         }
     }
 
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    pub fn builder(base_path: impl Into<String>) -> JupiterSwapApiClientBuilder {
+        JupiterSwapApiClientBuilder::new(base_path)
+    }
+
+    /// Establish connections to the base path, the API base path and every fallback base path ahead of time
+    ///
+    /// Requests carry the same API key and headers as other calls, and go through the rate limiter and interceptors.
+    /// The status code is ignored, any HTTP response counts as success and only connection failures are reported.
+    pub async fn warmup(&self) -> Result<(), JupiterError> {
+        let mut base_paths = Vec::<&String>::new();
+        for base_path in [&self.base_path, &self.api_base_path]
+            .into_iter()
+            .chain(&self.fallback_base_paths)
+        {
+            if !base_paths.contains(&base_path) {
+                base_paths.push(base_path);
+            }
+        }
+        for base_path in base_paths {
+            if let Some(rate_limiter) = &self.rate_limiter {
+                rate_limiter.acquire().await;
+            }
+            let mut request = self
+                .with_defaults(self.client.head(base_path), &RequestOptions::default())
+                .build()
+                .map_err(send_error)?;
+            for interceptor in &self.interceptors {
+                interceptor.on_request(&mut request);
+            }
+            let response = self.client.execute(request).await.map_err(send_error)?;
+            for interceptor in &self.interceptors {
+                interceptor.on_response(&response);
+            }
+        }
+        Ok(())
+    }
+
+    /// Stop accepting new requests on this client and all its clones, then wait for in-flight ones
+    ///
+    /// Pooled connections are closed once the last clone of the client is dropped.
+    pub async fn shutdown(&self, timeout: Duration) -> Result<(), JupiterError> {
+        self.in_flight.close();
+        if self.in_flight.drain(timeout).await {
+            Ok(())
+        } else {
+            Err(JupiterError::ShutdownTimeout {
+                in_flight: self.in_flight.count(),
+            })
+        }
+    }
+
+    pub fn is_shut_down(&self) -> bool {
+        self.in_flight.is_closed()
+    }
+
     pub async fn quote(&self, quote_request: &QuoteRequest) -> Result<QuoteResponse, JupiterError> {
-        let url = format!("{}/quote", self.base_path);
-        let extra_args = quote_request.quote_args.clone();
-        let internal_quote_request = InternalQuoteRequest::from(quote_request.clone());
-        let response = self
-            .client
-            .get(url)
-            .query(&internal_quote_request)
-            .query(&extra_args)
-            .send()
+        self.quote_with_options(quote_request, &RequestOptions::default())
             .await
-            .map_err(|e| JupiterError::RequestFailed {
-                status_code: reqwest::StatusCode::INTERNAL_SERVER_ERROR,
-                msg: e.to_string(),
-            })?;
-        check_status_code_and_deserialize(response).await
     }
 
+    pub async fn quote_with_options(
+        &self,
+        quote_request: &QuoteRequest,
+        options: &RequestOptions,
+    ) -> Result<QuoteResponse, JupiterError> {
+        self.quote_with_meta(quote_request, options)
+            .await
+            .map(|(quote_response, _)| quote_response)
+    }
+
+    pub async fn quote_with_meta(
+        &self,
+        quote_request: &QuoteRequest,
+        options: &RequestOptions,
+    ) -> Result<(QuoteResponse, ResponseMeta), JupiterError> {
+        quote_request.validate()?;
+        let path = self.swap_api_path(Endpoint::Quote)?;
+        let query = quote_request.to_query_string()?;
+        let (quote_response, response_meta) = self
+            .send::<QuoteResponse>(
+                Endpoint::Quote,
+                |base_path| self.client.get(format!("{base_path}{path}?{query}")),
+                options,
+            )
+            .await?;
+        if self.check_quote_invariants {
+            quote_response.check_invariants()?;
+        }
+        Ok((quote_response, response_meta))
+    }
+
+    /// URL requested by [`JupiterSwapApiClient::quote`] on the first base path, e.g. to replay it with curl
+    ///
+    /// Headers like the API key aren't part of it.
+    pub fn quote_url(&self, quote_request: &QuoteRequest) -> Result<String, JupiterError> {
+        let path = self.swap_api_path(Endpoint::Quote)?;
+        let mut url = format!(
+            "{}{path}?{}",
+            self.base_path,
+            quote_request.to_query_string()?
+        );
+        if !self.default_query_params.is_empty() {
+            url.push('&');
+            url.push_str(
+                &serde_urlencoded::to_string(&self.default_query_params)
+                    .map_err(|e| JupiterError::InvalidRequest(e.to_string()))?,
+            );
+        }
+        Ok(url)
+    }
+
+    /// `extra_args` are sent as query parameters, for parameters not yet typed in [`SwapRequest`]
     pub async fn swap(
         &self,
         swap_request: &SwapRequest,
         extra_args: Option<HashMap<String, String>>,
     ) -> Result<SwapResponse, JupiterError> {
-        let response = self
-            .client
-            .post(format!("{}/swap", self.base_path))
-            .query(&extra_args)
-            .json(swap_request)
-            .send()
+        self.swap_with_options(swap_request, extra_args, &RequestOptions::default())
+            .await
+    }
+
+    pub async fn swap_with_options(
+        &self,
+        swap_request: &SwapRequest,
+        extra_args: Option<HashMap<String, String>>,
+        options: &RequestOptions,
+    ) -> Result<SwapResponse, JupiterError> {
+        self.swap_with_meta(swap_request, extra_args, options)
             .await
-            .map_err(|e| JupiterError::RequestFailed {
-                status_code: reqwest::StatusCode::INTERNAL_SERVER_ERROR,
-                msg: e.to_string(),
-            })?;
-        check_status_code_and_deserialize(response).await
+            .map(|(swap_response, _)| swap_response)
     }
 
+    pub async fn swap_with_meta(
+        &self,
+        swap_request: &SwapRequest,
+        extra_args: Option<HashMap<String, String>>,
+        options: &RequestOptions,
+    ) -> Result<(SwapResponse, ResponseMeta), JupiterError> {
+        swap_request.validate()?;
+        let path = self.swap_api_path(Endpoint::Swap)?;
+        self.send(
+            Endpoint::Swap,
+            |base_path| {
+                self.client
+                    .post(format!("{base_path}{path}"))
+                    .query(&extra_args)
+                    .json(swap_request)
+            },
+            options,
+        )
+        .await
+    }
+
+    /// `extra_args` are sent as query parameters, like with [`JupiterSwapApiClient::swap`]
     pub async fn swap_instructions(
         &self,
         swap_request: &SwapRequest,
+        extra_args: Option<HashMap<String, String>>,
+    ) -> Result<SwapInstructionsResponse, JupiterError> {
+        self.swap_instructions_with_options(swap_request, extra_args, &RequestOptions::default())
+            .await
+    }
+
+    pub async fn swap_instructions_with_options(
+        &self,
+        swap_request: &SwapRequest,
+        extra_args: Option<HashMap<String, String>>,
+        options: &RequestOptions,
     ) -> Result<SwapInstructionsResponse, JupiterError> {
+        self.swap_instructions_with_meta(swap_request, extra_args, options)
+            .await
+            .map(|(swap_instructions_response, _)| swap_instructions_response)
+    }
+
+    pub async fn swap_instructions_with_meta(
+        &self,
+        swap_request: &SwapRequest,
+        extra_args: Option<HashMap<String, String>>,
+        options: &RequestOptions,
+    ) -> Result<(SwapInstructionsResponse, ResponseMeta), JupiterError> {
+        swap_request.validate()?;
+        let path = self.swap_api_path(Endpoint::SwapInstructions)?;
+        let (response, response_meta) = self
+            .send::<SwapInstructionsResponseInternal>(
+                Endpoint::SwapInstructions,
+                |base_path| {
+                    self.client
+                        .post(format!("{base_path}{path}"))
+                        .query(&extra_args)
+                        .json(swap_request)
+                },
+                options,
+            )
+            .await?;
+        Ok((response.try_into()?, response_meta))
+    }
+
+    /// Labels of the AMM programs, to render route plans and transaction logs
+    pub async fn program_id_to_label(&self) -> Result<HashMap<Pubkey, String>, JupiterError> {
+        let path = self.swap_api_path(Endpoint::ProgramIdToLabel)?;
+        let (labels, _) = self
+            .send::<HashMap<String, String>>(
+                Endpoint::ProgramIdToLabel,
+                |base_path| self.client.get(format!("{base_path}{path}")),
+                &RequestOptions::default(),
+            )
+            .await?;
+        labels
+            .into_iter()
+            .map(|(program_id, label)| Ok((parse_pubkey(&program_id, "program id")?, label)))
+            .collect()
+    }
+
+    /// Path of `endpoint` in the layout of [`JupiterSwapApiClient::api_version`]
+    pub(crate) fn swap_api_path(&self, endpoint: Endpoint) -> Result<&'static str, JupiterError> {
+        self.api_version.path(endpoint).ok_or_else(|| {
+            JupiterError::InvalidRequest(format!(
+                "{endpoint:?} isn't served by the {:?} API",
+                self.api_version
+            ))
+        })
+    }
+
+    /// `build_request` is called with the base path of every attempt, see [`Endpoint::is_swap_api`]
+    async fn send<T: DeserializeOwned>(
+        &self,
+        endpoint: Endpoint,
+        build_request: impl Fn(&str) -> RequestBuilder,
+        options: &RequestOptions,
+    ) -> Result<(T, ResponseMeta), JupiterError> {
+        let Some(_in_flight) = self.in_flight.enter() else {
+            return Err(JupiterError::ShutDown);
+        };
+
+        // Generated once per call so that retries and failovers reuse the same key
+        let generated_options;
+        let options = if options.idempotency_key.is_none()
+            && self.generate_idempotency_keys
+            && endpoint.is_post()
+        {
+            generated_options = RequestOptions {
+                idempotency_key: Some(generate_idempotency_key()),
+                ..options.clone()
+            };
+            &generated_options
+        } else {
+            options
+        };
+
+        let Some(retry_policy) = &self.retry_policy else {
+            return self
+                .send_with_failover(endpoint, &build_request, options)
+                .await;
+        };
+
+        let mut attempt = 1;
+        loop {
+            match self
+                .send_with_failover(endpoint, &build_request, options)
+                .await
+            {
+                Err(e) if attempt < retry_policy.max_attempts && retry_policy.should_retry(&e) => {
+                    tokio::time::sleep(retry_policy.delay_for(&e, attempt)).await;
+                    attempt += 1;
+                }
+                result => {
+                    return result.map(|(value, mut response_meta)| {
+                        response_meta.attempts = attempt;
+                        (value, response_meta)
+                    })
+                }
+            }
+        }
+    }
+
+    /// Try the base path first, then every fallback base path in order
+    ///
+    /// Endpoints outside of the swap API only use the API base path.
+    async fn send_with_failover<T: DeserializeOwned>(
+        &self,
+        endpoint: Endpoint,
+        build_request: &impl Fn(&str) -> RequestBuilder,
+        options: &RequestOptions,
+    ) -> Result<(T, ResponseMeta), JupiterError> {
+        let fallback_base_paths = if endpoint.is_swap_api() {
+            self.fallback_base_paths.as_slice()
+        } else {
+            &[]
+        };
+        let base_path = if endpoint.is_swap_api() {
+            &self.base_path
+        } else {
+            &self.api_base_path
+        };
+        let mut base_paths = std::iter::once(base_path)
+            .chain(fallback_base_paths)
+            .peekable();
+        loop {
+            let base_path = base_paths.next().expect("at least the base path is tried");
+            match self
+                .send_once(endpoint, build_request(base_path), options)
+                .await
+            {
+                Err(e) if base_paths.peek().is_some() && should_fail_over(&e) => continue,
+                // Failures are reported through `on_error`
+                result => {
+                    return result.map(|(value, mut response_meta)| {
+                        if let Some(on_served) = &self.on_served {
+                            on_served(endpoint, base_path);
+                        }
+                        response_meta.base_path.clone_from(base_path);
+                        (value, response_meta)
+                    });
+                }
+            }
+        }
+    }
+
+    /// Default query parameters and headers, then the ones of `options`, and the API key
+    fn with_defaults(&self, request: RequestBuilder, options: &RequestOptions) -> RequestBuilder {
+        let request = request
+            .query(&self.default_query_params)
+            .query(&options.query_params)
+            .headers(self.default_headers.clone())
+            .headers(options.headers.clone());
+        let request = match options.api_key.as_ref().or(self.api_key.as_ref()) {
+            Some(api_key) => request.header(API_KEY_HEADER, api_key),
+            None => request,
+        };
+        let request = match &self.expected_api_version {
+            Some(api_version) => request.header(API_VERSION_HEADER, api_version),
+            None => request,
+        };
+        match &options.idempotency_key {
+            Some(idempotency_key) => request.header(IDEMPOTENCY_KEY_HEADER, idempotency_key),
+            None => request,
+        }
+    }
+
+    async fn send_once<T: DeserializeOwned>(
+        &self,
+        endpoint: Endpoint,
+        request: RequestBuilder,
+        options: &RequestOptions,
+    ) -> Result<(T, ResponseMeta), JupiterError> {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire().await;
+        }
+        let request = self.with_defaults(request, options);
+        let request = match options.timeout.or(self.timeouts.get(endpoint)) {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        };
+        let mut request = request.build().map_err(send_error)?;
+        for interceptor in &self.interceptors {
+            interceptor.on_request(&mut request);
+        }
+        let context = RequestContext::new(endpoint, &request);
+        let fail = |e: JupiterError| {
+            let e = e.with_request_context(&context);
+            if let Some(on_error) = &self.on_error {
+                on_error(&e, &context);
+            }
+            e
+        };
+        let start = Instant::now();
         let response = self
             .client
-            .post(format!("{}/swap-instructions", self.base_path))
-            .json(swap_request)
-            .send()
+            .execute(request)
             .await
-            .map_err(|e| JupiterError::RequestFailed {
-                status_code: reqwest::StatusCode::INTERNAL_SERVER_ERROR,
-                msg: e.to_string(),
-            })?;
-        check_status_code_and_deserialize::<SwapInstructionsResponseInternal>(response)
-            .await
-            .map(Into::into)
+            .map_err(|e| fail(send_error(e)))?;
+        for interceptor in &self.interceptors {
+            interceptor.on_response(&response);
+        }
+        let status_code = response.status();
+        let headers = response.headers().clone();
+        let value = check_status_code_and_deserialize(
+            response,
+            endpoint,
+            self.deserialization_mode,
+            self.max_response_body_size
+                .map(|limit| limit.max(endpoint.min_response_body_size_limit())),
+            self.expected_api_version.as_deref(),
+        )
+        .await
+        .map_err(fail)?;
+        let response_meta = ResponseMeta {
+            status_code,
+            headers,
+            latency: start.elapsed(),
+            base_path: String::new(),
+            attempts: 1,
+        };
+        Ok((value, response_meta))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn api_error_code_from_string_error_code() {
+        assert_eq!(
+            api_error_code(&json!({ "errorCode": "NO_ROUTES_FOUND" })),
+            JupiterApiErrorCode::NoRoutesFound
+        );
+    }
+
+    #[test]
+    fn api_error_code_from_numeric_error_code() {
+        assert_eq!(
+            api_error_code(&json!({ "errorCode": 429 })),
+            JupiterApiErrorCode::Unknown("429".into())
+        );
+    }
+
+    #[test]
+    fn api_error_code_from_code() {
+        assert_eq!(
+            api_error_code(&json!({ "code": "TOKEN_NOT_TRADABLE" })),
+            JupiterApiErrorCode::TokenNotTradable
+        );
+        assert_eq!(
+            api_error_code(&json!({ "code": 1 })),
+            JupiterApiErrorCode::Unknown("1".into())
+        );
+    }
+
+    #[test]
+    fn api_error_code_null_error_code_falls_back_to_code() {
+        assert_eq!(
+            api_error_code(&json!({ "errorCode": null, "code": "MARKET_NOT_FOUND" })),
+            JupiterApiErrorCode::MarketNotFound
+        );
+    }
+
+    #[test]
+    fn api_error_code_missing_or_not_scalar() {
+        let unknown = JupiterApiErrorCode::Unknown(String::new());
+        assert_eq!(api_error_code(&json!({ "error": "failed" })), unknown);
+        assert_eq!(api_error_code(&json!({ "errorCode": null })), unknown);
+        assert_eq!(
+            api_error_code(&json!({ "errorCode": ["NO_ROUTES_FOUND"] })),
+            unknown
+        );
+        assert_eq!(api_error_code(&json!({ "code": { "value": 1 } })), unknown);
     }
 }
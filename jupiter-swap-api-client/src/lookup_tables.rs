@@ -0,0 +1,69 @@
+//! Address lookup tables of swap instructions, resolved before compiling the transaction
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::swap::SwapInstructionsResponse;
+
+/// Most accounts a single `getMultipleAccounts` call returns
+pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+impl SwapInstructionsResponse {
+    /// Lookup table addresses without duplicates, in their original order
+    pub fn unique_address_lookup_table_addresses(&self) -> Vec<Pubkey> {
+        let mut addresses = Vec::with_capacity(self.address_lookup_table_addresses.len());
+        for address in &self.address_lookup_table_addresses {
+            if !addresses.contains(address) {
+                addresses.push(*address);
+            }
+        }
+        addresses
+    }
+
+    /// Unique lookup table addresses in batches of `getMultipleAccounts` calls
+    pub fn address_lookup_table_batches(&self) -> Vec<Vec<Pubkey>> {
+        self.unique_address_lookup_table_addresses()
+            .chunks(MAX_MULTIPLE_ACCOUNTS)
+            .map(<[Pubkey]>::to_vec)
+            .collect()
+    }
+}
+
+#[cfg(feature = "solana")]
+mod rpc {
+    use solana_client::nonblocking::rpc_client::RpcClient;
+    use solana_sdk::address_lookup_table::{state::AddressLookupTable, AddressLookupTableAccount};
+
+    use crate::{swap::SwapInstructionsResponse, JupiterError};
+
+    impl SwapInstructionsResponse {
+        /// Fetches and decodes the lookup tables, to pass to [`SwapInstructionsResponse::compile_transaction`]
+        pub async fn fetch_address_lookup_tables(
+            &self,
+            rpc_client: &RpcClient,
+        ) -> Result<Vec<AddressLookupTableAccount>, JupiterError> {
+            let mut address_lookup_tables =
+                Vec::with_capacity(self.address_lookup_table_addresses.len());
+            for batch in self.address_lookup_table_batches() {
+                let accounts = rpc_client.get_multiple_accounts(&batch).await?;
+                for (key, account) in batch.into_iter().zip(accounts) {
+                    let account = account.ok_or_else(|| {
+                        JupiterError::MalformedResponse(format!(
+                            "address lookup table {key} not found"
+                        ))
+                    })?;
+                    let address_lookup_table = AddressLookupTable::deserialize(&account.data)
+                        .map_err(|e| {
+                            JupiterError::MalformedResponse(format!(
+                                "invalid address lookup table {key}: {e}"
+                            ))
+                        })?;
+                    address_lookup_tables.push(AddressLookupTableAccount {
+                        key,
+                        addresses: address_lookup_table.addresses.to_vec(),
+                    });
+                }
+            }
+            Ok(address_lookup_tables)
+        }
+    }
+}
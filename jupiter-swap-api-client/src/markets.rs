@@ -0,0 +1,43 @@
+//! Markets endpoint, to make freshly launched pools routable
+
+use serde_json::Value;
+use solana_account_decoder::{encode_ui_account, UiAccountEncoding};
+use solana_sdk::{account::ReadableAccount, pubkey::Pubkey};
+
+use crate::{
+    request_options::{Endpoint, RequestOptions},
+    transaction_config::KeyedUiAccount,
+    JupiterError, JupiterSwapApiClient,
+};
+
+/// AMM state account to index, with the params of the market cache format when the AMM requires them
+pub type MarketRequest = KeyedUiAccount;
+
+impl KeyedUiAccount {
+    /// Base64 encodes `account`
+    pub fn new(pubkey: &Pubkey, account: &impl ReadableAccount, params: Option<Value>) -> Self {
+        Self {
+            pubkey: pubkey.to_string(),
+            ui_account: encode_ui_account(pubkey, account, UiAccountEncoding::Base64, None, None),
+            params,
+        }
+    }
+}
+
+impl JupiterSwapApiClient {
+    /// Submit a market for indexing, served by self-hosted and paid deployments
+    pub async fn add_market(&self, market_request: &MarketRequest) -> Result<(), JupiterError> {
+        let path = self.swap_api_path(Endpoint::Markets)?;
+        self.send::<serde::de::IgnoredAny>(
+            Endpoint::Markets,
+            |base_path| {
+                self.client
+                    .post(format!("{base_path}{path}"))
+                    .json(market_request)
+            },
+            &RequestOptions::default(),
+        )
+        .await
+        .map(|_| ())
+    }
+}
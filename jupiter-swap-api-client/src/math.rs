@@ -0,0 +1,92 @@
+//! Checked amount math, computed in u128 so that multiplying by bps never overflows u64
+
+use rust_decimal::Decimal;
+
+use crate::quote::{QuoteResponse, SwapMode, MAX_BPS};
+
+/// `amount * bps / 10000`, rounded down
+pub fn bps_of(amount: u64, bps: u16) -> Option<u64> {
+    mul_div_floor(amount, u64::from(bps), u64::from(MAX_BPS))
+}
+
+/// `amount` reduced by `bps`, rounded down, e.g. the minimum out amount for a slippage
+pub fn sub_bps(amount: u64, bps: u16) -> Option<u64> {
+    let remaining_bps = MAX_BPS.checked_sub(bps)?;
+    mul_div_floor(amount, u64::from(remaining_bps), u64::from(MAX_BPS))
+}
+
+/// `amount` increased by `bps`, rounded up, e.g. the maximum in amount for a slippage
+pub fn add_bps(amount: u64, bps: u16) -> Option<u64> {
+    mul_div_ceil(
+        amount,
+        u64::from(MAX_BPS) + u64::from(bps),
+        u64::from(MAX_BPS),
+    )
+}
+
+/// Atomic `amount` in whole tokens, `None` for more than 28 decimals
+pub fn ui_amount(amount: u64, decimals: u8) -> Option<Decimal> {
+    Decimal::try_from_i128_with_scale(i128::from(amount), u32::from(decimals)).ok()
+}
+
+/// `amount * numerator / denominator`, rounded down, `None` on overflow or division by 0
+pub fn mul_div_floor(amount: u64, numerator: u64, denominator: u64) -> Option<u64> {
+    let product = u128::from(amount).checked_mul(u128::from(numerator))?;
+    u64::try_from(product.checked_div(u128::from(denominator))?).ok()
+}
+
+/// `amount * numerator / denominator`, rounded up, `None` on overflow or division by 0
+pub fn mul_div_ceil(amount: u64, numerator: u64, denominator: u64) -> Option<u64> {
+    if denominator == 0 {
+        return None;
+    }
+    let product = u128::from(amount).checked_mul(u128::from(numerator))?;
+    u64::try_from(product.div_ceil(u128::from(denominator))).ok()
+}
+
+impl QuoteResponse {
+    /// Out amount reduced by `slippage_bps`
+    pub fn out_amount_with_slippage(&self, slippage_bps: u16) -> Option<u64> {
+        sub_bps(self.out_amount, slippage_bps)
+    }
+
+    /// In amount increased by `slippage_bps`
+    pub fn in_amount_with_slippage(&self, slippage_bps: u16) -> Option<u64> {
+        add_bps(self.in_amount, slippage_bps)
+    }
+
+    /// Most the ExactOut swap can spend with the quote slippage, `None` for ExactIn where the input is fixed
+    pub fn max_in_amount_with_slippage(&self) -> Option<u64> {
+        match self.swap_mode {
+            SwapMode::ExactIn => None,
+            SwapMode::ExactOut => self.in_amount_with_slippage(self.slippage_bps),
+        }
+    }
+
+    /// Out amount after deducting a fee of `fee_bps`
+    pub fn out_amount_after_fee(&self, fee_bps: u16) -> Option<u64> {
+        sub_bps(self.out_amount, fee_bps)
+    }
+
+    /// Out amount per in amount in atomic units, not adjusted for decimals
+    pub fn price_ratio(&self) -> Option<Decimal> {
+        Decimal::from(self.out_amount).checked_div(Decimal::from(self.in_amount))
+    }
+
+    /// Output tokens received per input token
+    pub fn effective_price(&self, input_decimals: u8, output_decimals: u8) -> Option<Decimal> {
+        ui_amount(self.out_amount, output_decimals)?
+            .checked_div(ui_amount(self.in_amount, input_decimals)?)
+    }
+
+    /// Input tokens spent per output token
+    pub fn inverse_price(&self, input_decimals: u8, output_decimals: u8) -> Option<Decimal> {
+        ui_amount(self.in_amount, input_decimals)?
+            .checked_div(ui_amount(self.out_amount, output_decimals)?)
+    }
+
+    /// Price impact as a fraction, e.g. `0.01` for 1%
+    pub fn price_impact(&self) -> Decimal {
+        self.price_impact_pct
+    }
+}
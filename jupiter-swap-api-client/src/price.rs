@@ -0,0 +1,110 @@
+//! Price API v2
+
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    request_options::{Endpoint, RequestOptions},
+    swap::parse_pubkey,
+    JupiterError, JupiterSwapApiClient,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ConfidenceLevel {
+    High,
+    Medium,
+    Low,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LastSwappedPrice {
+    /// Unix timestamp in seconds
+    pub last_jupiter_sell_at: Option<i64>,
+    pub last_jupiter_sell_price: Option<Decimal>,
+    pub last_jupiter_buy_at: Option<i64>,
+    pub last_jupiter_buy_price: Option<Decimal>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotedPrice {
+    pub buy_price: Option<Decimal>,
+    /// Unix timestamp in seconds
+    pub buy_at: Option<i64>,
+    pub sell_price: Option<Decimal>,
+    pub sell_at: Option<i64>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceExtraInfo {
+    pub last_swapped_price: Option<LastSwappedPrice>,
+    pub quoted_price: Option<QuotedPrice>,
+    pub confidence_level: Option<ConfidenceLevel>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenPrice {
+    pub id: String,
+    /// e.g. `derivedPrice` or `buyPrice`
+    #[serde(rename = "type")]
+    pub price_type: String,
+    /// Price of one whole token in the vs token, USDC by default
+    pub price: Decimal,
+    pub extra_info: Option<PriceExtraInfo>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct PriceResponseInternal {
+    /// `null` for mints without a price
+    data: HashMap<String, Option<TokenPrice>>,
+}
+
+impl JupiterSwapApiClient {
+    /// Prices of `mints`, in `vs_token` or USDC when `None`
+    ///
+    /// Mints without a reliable price are missing from the result.
+    pub async fn get_prices(
+        &self,
+        mints: &[Pubkey],
+        vs_token: Option<&Pubkey>,
+    ) -> Result<HashMap<Pubkey, TokenPrice>, JupiterError> {
+        if mints.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let ids = mints
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        let vs_token = vs_token.map(ToString::to_string);
+        let (response, _) = self
+            .send::<PriceResponseInternal>(
+                Endpoint::Price,
+                |api_base_path| {
+                    self.client
+                        .get(format!("{api_base_path}/price/v2"))
+                        .query(&[("ids", &ids)])
+                        .query(&[("showExtraInfo", "true")])
+                        .query(&[("vsToken", &vs_token)])
+                },
+                &RequestOptions::default(),
+            )
+            .await?;
+        response
+            .data
+            .into_iter()
+            .filter_map(|(mint, price)| Some((mint, price?)))
+            .map(|(mint, price)| Ok((parse_pubkey(&mint, "price mint")?, price)))
+            .collect()
+    }
+}
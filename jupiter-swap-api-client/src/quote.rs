@@ -1,14 +1,19 @@
 //! Quote data structure for quoting and quote response
 //!
 
-use std::{collections::HashMap, str::FromStr};
+use std::{collections::HashMap, fmt, str::FromStr};
 
+use crate::dex::Dexes;
+use crate::referral::PlatformFeeConfig;
 use crate::route_plan_with_metadata::RoutePlanWithMetadata;
 use crate::serde_helpers::field_as_string;
+use crate::slippage::Slippage;
+use crate::{math, JupiterError};
 use anyhow::{anyhow, Error};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
+use thiserror::Error;
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -64,7 +69,8 @@ pub struct QuoteRequest {
     pub amount: u64,
     /// (ExactIn or ExactOut) Defaults to ExactIn.
     /// ExactOut is for supporting use cases where you need an exact token amount, like payments.
-    /// In this case the slippage is on the input token.
+    /// In this case the slippage is on the input token, `amount` is the output amount
+    /// and the platform fee is taken from the input token, so the fee account has to be for the input mint.
     pub swap_mode: Option<SwapMode>,
     /// Allowed slippage in basis points
     pub slippage_bps: u16,
@@ -83,24 +89,302 @@ pub struct QuoteRequest {
     pub platform_fee_bps: Option<u8>,
     pub dexes: Option<Dexes>,
     pub exclude_dexes: Option<Dexes>,
-    /// Quote only direct routes
+    /// Quote only direct routes, through a single market.
+    /// Faster to quote and smaller transactions, at the cost of a worse price on illiquid pairs
+    /// or no route at all when the pair has no direct market.
     pub only_direct_routes: Option<bool>,
     /// Quote fit into legacy transaction
     pub as_legacy_transaction: Option<bool>,
     /// Restrict intermediate tokens to a top token set that has stable liquidity.
     /// This will help to ease potential high slippage error rate when swapping with minimal impact on pricing.
+    /// Also lowers quote latency as fewer routes are explored.
     pub restrict_intermediate_tokens: Option<bool>,
     /// Find a route given a maximum number of accounts involved,
     /// this might dangerously limit routing ending up giving a bad price.
-    /// The max is an estimation and not the exact count
+    /// The max is an estimation and not the exact count, see [`QuoteResponse::estimated_account_count`]
     pub max_accounts: Option<usize>,
     // Quote type to be used for routing, switches the algorithm
     pub quote_type: Option<String>,
     // Extra args which are quote type specific to allow controlling settings from the top level
+    /// Also the escape hatch for query params without a typed field yet
     pub quote_args: Option<HashMap<String, String>>,
     // enable only full liquid markets as intermediate tokens
     pub prefer_liquid_dexes: Option<bool>,
+    /// Pick intermediate tokens by token category, e.g. stables or LSTs, instead of a fixed top token set
+    pub token_category_based_intermediate_tokens: Option<bool>,
+    /// Let the route use intermediate tokens discovered at quote time
+    pub support_dynamic_intermediate_tokens: Option<bool>,
+    /// Quote for a swap estimated with dynamic slippage
+    pub dynamic_slippage: Option<bool>,
+    /// Only route through AMMs compatible with Jito bundles
+    pub for_jito_bundle: Option<bool>,
+}
+
+/// Slippage and fees can't exceed the whole amount
+pub const MAX_BPS: u16 = 10_000;
+
+impl QuoteRequest {
+    /// Starts from the API defaults and [`DEFAULT_SLIPPAGE_BPS`]
+    pub fn builder(input_mint: Pubkey, output_mint: Pubkey, amount: u64) -> QuoteRequestBuilder {
+        QuoteRequestBuilder {
+            request: QuoteRequest {
+                input_mint,
+                output_mint,
+                amount,
+                slippage_bps: DEFAULT_SLIPPAGE_BPS,
+                ..QuoteRequest::default()
+            },
+            slippage: None,
+        }
+    }
+
+    /// Query string sent to `/quote`, followed by the `quote_args`
+    ///
+    /// The client appends its default query params and the ones of the [`RequestOptions`](crate::request_options::RequestOptions).
+    pub fn to_query_string(&self) -> Result<String, JupiterError> {
+        let invalid = |e: serde_urlencoded::ser::Error| JupiterError::InvalidRequest(e.to_string());
+        let mut query = serde_urlencoded::to_string(InternalQuoteRequest::from(self.clone()))
+            .map_err(invalid)?;
+        if let Some(quote_args) = self.quote_args.as_ref().filter(|args| !args.is_empty()) {
+            query.push('&');
+            query.push_str(&serde_urlencoded::to_string(quote_args).map_err(invalid)?);
+        }
+        Ok(query)
+    }
+
+    /// Stable hash of the query params, e.g. to cache quotes or coalesce identical requests
+    ///
+    /// Without `include_amount`, requests differing only by amount share a key.
+    /// Stable across processes and releases as long as the query params don't change.
+    pub fn cache_key(&self, include_amount: bool) -> u64 {
+        let mut request = self.clone();
+        if !include_amount {
+            request.amount = 0;
+        }
+        let mut quote_args = request
+            .quote_args
+            .take()
+            .unwrap_or_default()
+            .into_iter()
+            .collect::<Vec<_>>();
+        quote_args.sort();
+        let query =
+            serde_urlencoded::to_string(InternalQuoteRequest::from(request)).unwrap_or_default();
+        let quote_args = serde_urlencoded::to_string(quote_args).unwrap_or_default();
+        fnv1a(
+            [query.as_bytes(), b"&", quote_args.as_bytes()]
+                .concat()
+                .as_slice(),
+        )
+    }
+
+    /// Catch obvious mistakes locally instead of spending rate limit quota on them
+    pub fn validate(&self) -> Result<(), JupiterError> {
+        let invalid = |msg: &str| Err(JupiterError::InvalidRequest(msg.to_string()));
+        if self.amount == 0 {
+            return invalid("amount has to be greater than 0");
+        }
+        if self.input_mint == Pubkey::default() || self.output_mint == Pubkey::default() {
+            return invalid("input_mint and output_mint have to be set");
+        }
+        if self.input_mint == self.output_mint {
+            return invalid("input_mint and output_mint have to differ");
+        }
+        if self.slippage_bps > MAX_BPS {
+            return invalid("slippage_bps can't exceed 10000");
+        }
+        if self.max_auto_slippage_bps.is_some_and(|bps| bps > MAX_BPS) {
+            return invalid("max_auto_slippage_bps can't exceed 10000");
+        }
+        if self.swap_mode == Some(SwapMode::ExactOut) && self.dynamic_slippage == Some(true) {
+            return invalid("dynamic_slippage isn't supported with ExactOut");
+        }
+        let quote_args = self.quote_args.as_ref();
+        let in_quote_args = |key: &str| quote_args.is_some_and(|args| args.contains_key(key));
+        if self.only_direct_routes.is_some() && in_quote_args("onlyDirectRoutes") {
+            return invalid("only_direct_routes is also set in quote_args");
+        }
+        if self.restrict_intermediate_tokens.is_some()
+            && in_quote_args("restrictIntermediateTokens")
+        {
+            return invalid("restrict_intermediate_tokens is also set in quote_args");
+        }
+        Ok(())
+    }
+}
+
+/// 64 bit FNV-1a, unlike `DefaultHasher` its output never changes
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Slippage of a [`QuoteRequestBuilder`] unless set
+pub const DEFAULT_SLIPPAGE_BPS: u16 = 50;
+
+/// Fluent construction of a [`QuoteRequest`], see [`QuoteRequest::builder`]
+#[derive(Debug, Clone)]
+pub struct QuoteRequestBuilder {
+    request: QuoteRequest,
+    slippage: Option<Slippage>,
 }
+
+impl QuoteRequestBuilder {
+    /// Replaces the atomic amount, see [`QuoteRequestBuilder::amount`] to pass an [`Amount`](crate::amount::Amount)
+    pub fn raw_amount(mut self, amount: u64) -> Self {
+        self.request.amount = amount;
+        self
+    }
+
+    /// Replaces [`QuoteRequestBuilder::slippage_bps`] and `dynamic_slippage`
+    pub fn slippage(mut self, slippage: impl Into<Slippage>) -> Self {
+        self.slippage = Some(slippage.into());
+        self
+    }
+
+    pub fn slippage_bps(mut self, slippage_bps: u16) -> Self {
+        self.request.slippage_bps = slippage_bps;
+        self
+    }
+
+    pub fn compute_auto_slippage(mut self, compute_auto_slippage: bool) -> Self {
+        self.request.compute_auto_slippage = compute_auto_slippage;
+        self
+    }
+
+    /// Only route through these dexes, e.g. `[Dex::Whirlpool, Dex::MeteoraDlmm]` or `"Whirlpool,Meteora DLMM"`
+    pub fn dexes(mut self, dexes: impl Into<Dexes>) -> Self {
+        self.request.dexes = Some(dexes.into());
+        self
+    }
+
+    pub fn exclude_dexes(mut self, exclude_dexes: impl Into<Dexes>) -> Self {
+        self.request.exclude_dexes = Some(exclude_dexes.into());
+        self
+    }
+
+    /// Sets `platform_fee_bps`, apply the same config to the swap with [`PlatformFeeConfig::apply_to_swap`]
+    pub fn platform_fee(mut self, platform_fee: &PlatformFeeConfig) -> Self {
+        platform_fee.apply_to_quote(&mut self.request);
+        self
+    }
+
+    pub fn quote_type(mut self, quote_type: impl Into<String>) -> Self {
+        self.request.quote_type = Some(quote_type.into());
+        self
+    }
+
+    /// Adds a query param without a typed field yet, see [`QuoteRequest::quote_args`]
+    pub fn quote_arg(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.request
+            .quote_args
+            .get_or_insert_with(HashMap::new)
+            .insert(key.into(), value.into());
+        self
+    }
+
+    pub fn swap_mode(mut self, swap_mode: SwapMode) -> Self {
+        self.request.swap_mode = Some(swap_mode);
+        self
+    }
+
+    pub fn auto_slippage(mut self, auto_slippage: bool) -> Self {
+        self.request.auto_slippage = Some(auto_slippage);
+        self
+    }
+
+    /// Let the API pick the slippage, at most `max_bps`, see [`QuoteResponse::effective_slippage_bps`]
+    pub fn auto_slippage_up_to(self, max_bps: u16) -> Self {
+        self.auto_slippage(true).max_auto_slippage_bps(max_bps)
+    }
+
+    pub fn max_auto_slippage_bps(mut self, max_auto_slippage_bps: u16) -> Self {
+        self.request.max_auto_slippage_bps = Some(max_auto_slippage_bps);
+        self
+    }
+
+    pub fn auto_slippage_collision_usd_value(
+        mut self,
+        auto_slippage_collision_usd_value: u32,
+    ) -> Self {
+        self.request.auto_slippage_collision_usd_value = Some(auto_slippage_collision_usd_value);
+        self
+    }
+
+    pub fn minimize_slippage(mut self, minimize_slippage: bool) -> Self {
+        self.request.minimize_slippage = Some(minimize_slippage);
+        self
+    }
+
+    pub fn platform_fee_bps(mut self, platform_fee_bps: u8) -> Self {
+        self.request.platform_fee_bps = Some(platform_fee_bps);
+        self
+    }
+
+    pub fn only_direct_routes(mut self, only_direct_routes: bool) -> Self {
+        self.request.only_direct_routes = Some(only_direct_routes);
+        self
+    }
+
+    pub fn as_legacy_transaction(mut self, as_legacy_transaction: bool) -> Self {
+        self.request.as_legacy_transaction = Some(as_legacy_transaction);
+        self
+    }
+
+    pub fn restrict_intermediate_tokens(mut self, restrict_intermediate_tokens: bool) -> Self {
+        self.request.restrict_intermediate_tokens = Some(restrict_intermediate_tokens);
+        self
+    }
+
+    pub fn max_accounts(mut self, max_accounts: usize) -> Self {
+        self.request.max_accounts = Some(max_accounts);
+        self
+    }
+
+    pub fn prefer_liquid_dexes(mut self, prefer_liquid_dexes: bool) -> Self {
+        self.request.prefer_liquid_dexes = Some(prefer_liquid_dexes);
+        self
+    }
+
+    pub fn token_category_based_intermediate_tokens(
+        mut self,
+        token_category_based_intermediate_tokens: bool,
+    ) -> Self {
+        self.request.token_category_based_intermediate_tokens =
+            Some(token_category_based_intermediate_tokens);
+        self
+    }
+
+    pub fn support_dynamic_intermediate_tokens(
+        mut self,
+        support_dynamic_intermediate_tokens: bool,
+    ) -> Self {
+        self.request.support_dynamic_intermediate_tokens =
+            Some(support_dynamic_intermediate_tokens);
+        self
+    }
+
+    pub fn dynamic_slippage(mut self, dynamic_slippage: bool) -> Self {
+        self.request.dynamic_slippage = Some(dynamic_slippage);
+        self
+    }
+
+    pub fn for_jito_bundle(mut self, for_jito_bundle: bool) -> Self {
+        self.request.for_jito_bundle = Some(for_jito_bundle);
+        self
+    }
+
+    /// Fails with [`JupiterError::InvalidRequest`] as [`QuoteRequest::validate`] does
+    pub fn build(mut self) -> Result<QuoteRequest, JupiterError> {
+        if let Some(slippage) = self.slippage {
+            self.request.set_slippage(slippage)?;
+        }
+        self.request.validate()?;
+        Ok(self.request)
+    }
+}
+
 // Essentially the same as QuoteRequest, but without the extra args
 // as we pass the extra args separately
 #[derive(Serialize, Debug, Default, Clone)]
@@ -149,6 +433,21 @@ pub struct InternalQuoteRequest {
     pub quote_type: Option<String>,
     // enable only full liquid markets as intermediate tokens
     pub prefer_liquid_dexes: Option<bool>,
+    /// Pick intermediate tokens by token category, e.g. stables or LSTs, instead of a fixed top token set
+    pub token_category_based_intermediate_tokens: Option<bool>,
+    /// Let the route use intermediate tokens discovered at quote time
+    pub support_dynamic_intermediate_tokens: Option<bool>,
+    /// Quote for a swap estimated with dynamic slippage
+    pub dynamic_slippage: Option<bool>,
+    /// Only route through AMMs compatible with Jito bundles
+    pub for_jito_bundle: Option<bool>,
+}
+
+/// Query string, see [`QuoteRequest::to_query_string`]
+impl fmt::Display for QuoteRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_query_string().map_err(|_| fmt::Error)?)
+    }
 }
 
 impl From<QuoteRequest> for InternalQuoteRequest {
@@ -173,13 +472,15 @@ impl From<QuoteRequest> for InternalQuoteRequest {
             max_accounts: request.max_accounts,
             quote_type: request.quote_type,
             prefer_liquid_dexes: request.prefer_liquid_dexes,
+            token_category_based_intermediate_tokens: request
+                .token_category_based_intermediate_tokens,
+            support_dynamic_intermediate_tokens: request.support_dynamic_intermediate_tokens,
+            dynamic_slippage: request.dynamic_slippage,
+            for_jito_bundle: request.for_jito_bundle,
         }
     }
 }
 
-/// Comma delimited list of dex labels
-type Dexes = String;
-
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct PlatformFee {
@@ -200,10 +501,13 @@ pub struct QuoteResponse {
     #[serde(with = "field_as_string")]
     pub out_amount: u64,
     /// Not used by build transaction
+    ///
+    /// Min out amount for ExactIn and max in amount for ExactOut, see [`QuoteResponse::min_out_amount`] and [`QuoteResponse::max_in_amount`]
     #[serde(with = "field_as_string")]
     pub other_amount_threshold: u64,
     pub swap_mode: SwapMode,
     pub slippage_bps: u16,
+    /// Slippage picked by the API for `auto_slippage` requests, in basis points
     #[serde(skip_serializing_if = "Option::is_none")]
     pub computed_auto_slippage: Option<u16>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -216,3 +520,89 @@ pub struct QuoteResponse {
     #[serde(default)]
     pub time_taken: f64,
 }
+
+/// Inconsistency found by [`QuoteResponse::check_invariants`]
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum QuoteInvariantViolation {
+    #[error("out_amount is 0")]
+    ZeroOutAmount,
+    #[error("route_plan is empty")]
+    EmptyRoutePlan,
+    /// `other_amount_threshold` doesn't match the amount adjusted by `slippage_bps`
+    #[error("other_amount_threshold {actual} outside of [{min}, {max}]")]
+    OtherAmountThreshold { actual: u64, min: u64, max: u64 },
+    /// Steps swapping from the same mint have to split all of it
+    #[error("route_plan percentages from {input_mint} sum to {percent}")]
+    RoutePlanPercent { input_mint: Pubkey, percent: u32 },
+}
+
+/// Tolerated difference in `other_amount_threshold` due to rounding
+const THRESHOLD_ROUNDING_TOLERANCE: u64 = 1;
+
+impl QuoteResponse {
+    /// Slippage the swap is built with, computed by the API for `auto_slippage` requests
+    pub fn effective_slippage_bps(&self) -> u16 {
+        self.computed_auto_slippage.unwrap_or(self.slippage_bps)
+    }
+
+    /// Least the ExactIn swap receives, `None` for ExactOut where the output is fixed
+    pub fn min_out_amount(&self) -> Option<u64> {
+        (self.swap_mode == SwapMode::ExactIn).then_some(self.other_amount_threshold)
+    }
+
+    /// Most the ExactOut swap spends, `None` for ExactIn where the input is fixed
+    pub fn max_in_amount(&self) -> Option<u64> {
+        (self.swap_mode == SwapMode::ExactOut).then_some(self.other_amount_threshold)
+    }
+
+    /// Check that the amounts and route plan are consistent, to detect corrupted responses
+    pub fn check_invariants(&self) -> Result<(), QuoteInvariantViolation> {
+        if self.out_amount == 0 {
+            return Err(QuoteInvariantViolation::ZeroOutAmount);
+        }
+        if self.route_plan.is_empty() {
+            return Err(QuoteInvariantViolation::EmptyRoutePlan);
+        }
+
+        let slippage_bps = self.slippage_bps.min(MAX_BPS);
+        let (min, max) = match self.swap_mode {
+            SwapMode::ExactIn => (
+                math::sub_bps(self.out_amount, slippage_bps)
+                    .unwrap_or_default()
+                    .saturating_sub(THRESHOLD_ROUNDING_TOLERANCE),
+                self.out_amount,
+            ),
+            SwapMode::ExactOut => (
+                self.in_amount,
+                math::add_bps(self.in_amount, slippage_bps)
+                    .unwrap_or(u64::MAX)
+                    .saturating_add(THRESHOLD_ROUNDING_TOLERANCE),
+            ),
+        };
+        if !(min..=max).contains(&self.other_amount_threshold) {
+            return Err(QuoteInvariantViolation::OtherAmountThreshold {
+                actual: self.other_amount_threshold,
+                min,
+                max,
+            });
+        }
+
+        let mut percents = Vec::<(Pubkey, u32)>::new();
+        for step in &self.route_plan {
+            let input_mint = step.swap_info.input_mint;
+            match percents.iter_mut().find(|(mint, _)| *mint == input_mint) {
+                Some((_, percent)) => *percent += u32::from(step.percent),
+                None => percents.push((input_mint, u32::from(step.percent))),
+            }
+        }
+        if let Some((input_mint, percent)) =
+            percents.into_iter().find(|(_, percent)| *percent != 100)
+        {
+            return Err(QuoteInvariantViolation::RoutePlanPercent {
+                input_mint,
+                percent,
+            });
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+//! Ranking of quotes, e.g. to pick the best of several quotes with different settings
+
+use std::cmp::Ordering;
+
+use crate::quote::{QuoteResponse, SwapMode};
+
+/// What makes a quote better in [`QuoteResponse::compare`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteCriteria {
+    /// Highest out amount for ExactIn, lowest in amount for ExactOut
+    NetAmount,
+    /// Lowest price impact
+    PriceImpact,
+    /// Fewest swaps
+    Hops,
+    /// Fewest estimated accounts, see [`QuoteResponse::estimated_account_count`]
+    Accounts,
+}
+
+impl QuoteResponse {
+    /// `Ordering::Greater` when `self` is better than `other`
+    pub fn compare(&self, other: &QuoteResponse, criteria: QuoteCriteria) -> Ordering {
+        match criteria {
+            QuoteCriteria::NetAmount => match self.swap_mode {
+                SwapMode::ExactIn => self.out_amount.cmp(&other.out_amount),
+                SwapMode::ExactOut => other.in_amount.cmp(&self.in_amount),
+            },
+            QuoteCriteria::PriceImpact => other
+                .price_impact_pct
+                .abs()
+                .cmp(&self.price_impact_pct.abs()),
+            QuoteCriteria::Hops => other.route_plan.len().cmp(&self.route_plan.len()),
+            QuoteCriteria::Accounts => other
+                .estimated_account_count()
+                .cmp(&self.estimated_account_count()),
+        }
+    }
+
+    pub fn better_than(&self, other: &QuoteResponse, criteria: QuoteCriteria) -> bool {
+        self.compare(other, criteria) == Ordering::Greater
+    }
+}
+
+/// Sorts `quotes` best first by `criteria`, ties broken by the next criteria
+pub fn compare_quotes(quotes: &mut [QuoteResponse], criteria: &[QuoteCriteria]) {
+    quotes.sort_by(|a, b| {
+        criteria
+            .iter()
+            .map(|criteria| b.compare(a, *criteria))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+}
+
+/// Best of `quotes` by `criteria`, the first one on ties
+pub fn best_quote<'a>(
+    quotes: impl IntoIterator<Item = &'a QuoteResponse>,
+    criteria: QuoteCriteria,
+) -> Option<&'a QuoteResponse> {
+    quotes.into_iter().reduce(|best, quote| {
+        if quote.better_than(best, criteria) {
+            quote
+        } else {
+            best
+        }
+    })
+}
@@ -0,0 +1,105 @@
+use std::{
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use crate::JupiterError;
+
+/// Lowest rate accepted by [`RateLimiter::new`], one request per hour
+pub const MIN_REQUESTS_PER_SECOND: f64 = 1.0 / 3600.0;
+
+/// Token bucket limiting the rate of outgoing requests
+///
+/// Waiting callers reserve their token upfront, so they are served in the order they arrived.
+#[derive(Debug)]
+pub struct RateLimiter {
+    requests_per_second: f64,
+    burst: f64,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// `burst` is the number of requests allowed back to back
+    ///
+    /// Fails with [`JupiterError::InvalidConfig`] when `requests_per_second` is not finite or below
+    /// [`MIN_REQUESTS_PER_SECOND`].
+    pub fn new(requests_per_second: f64, burst: u32) -> Result<Self, JupiterError> {
+        if !(requests_per_second.is_finite() && requests_per_second >= MIN_REQUESTS_PER_SECOND) {
+            return Err(JupiterError::InvalidConfig(format!(
+                "rate limit has to be at least {MIN_REQUESTS_PER_SECOND} requests per second, got {requests_per_second}"
+            )));
+        }
+        let burst = f64::from(burst.max(1));
+        Ok(Self {
+            requests_per_second,
+            burst,
+            state: Mutex::new(BucketState {
+                tokens: burst,
+                last_refill: Instant::now(),
+            }),
+        })
+    }
+
+    /// Matches quotas expressed in requests per minute, allowing the full quota as a burst
+    pub fn per_minute(requests_per_minute: u32) -> Result<Self, JupiterError> {
+        Self::new(f64::from(requests_per_minute) / 60.0, requests_per_minute)
+    }
+
+    pub fn requests_per_second(&self) -> f64 {
+        self.requests_per_second
+    }
+
+    pub fn burst(&self) -> u32 {
+        self.burst as u32
+    }
+
+    /// Wait until a request is allowed to be sent
+    pub async fn acquire(&self) {
+        if let Some(wait) = self.reserve() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    fn reserve(&self) -> Option<Duration> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.requests_per_second).min(self.burst);
+        state.last_refill = now;
+        state.tokens -= 1.0;
+        (state.tokens < 0.0).then(|| {
+            Duration::try_from_secs_f64(-state.tokens / self.requests_per_second)
+                .unwrap_or(Duration::MAX)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_invalid_rates() {
+        for requests_per_second in [0.0, -1.0, f64::NAN, f64::INFINITY, 1e-9] {
+            assert!(RateLimiter::new(requests_per_second, 1).is_err());
+        }
+        assert!(RateLimiter::per_minute(0).is_err());
+    }
+
+    #[test]
+    fn waits_once_the_bucket_is_empty() {
+        let rate_limiter = RateLimiter::new(MIN_REQUESTS_PER_SECOND, 2).unwrap();
+        assert_eq!(rate_limiter.reserve(), None);
+        assert_eq!(rate_limiter.reserve(), None);
+        let wait = rate_limiter.reserve().unwrap();
+        assert!(wait > Duration::from_secs(3590) && wait <= Duration::from_secs(3600));
+        let wait = rate_limiter.reserve().unwrap();
+        assert!(wait > Duration::from_secs(7190) && wait <= Duration::from_secs(7200));
+    }
+}
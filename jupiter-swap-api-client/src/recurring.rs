@@ -0,0 +1,308 @@
+//! Recurring API v1, time based (DCA) and price based recurring swaps
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    request_options::{Endpoint, RequestOptions},
+    serde_helpers::{field_as_string, option_field_as_string},
+    swap::base64_serialize_deserialize,
+    JupiterError, JupiterSwapApiClient,
+};
+
+/// Swap `in_amount` over `number_of_orders` orders, one every `interval` seconds
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeRecurringParams {
+    /// Total amount of the input mint, split evenly between the orders
+    pub in_amount: u64,
+    pub number_of_orders: u64,
+    /// Seconds between orders
+    pub interval: u64,
+    /// Orders are skipped while the output price is below it
+    pub min_price: Option<f64>,
+    /// Orders are skipped while the output price is above it
+    pub max_price: Option<f64>,
+    /// Unix timestamp in seconds, starts right away when not set
+    pub start_at: Option<i64>,
+}
+
+impl TimeRecurringParams {
+    pub fn new(in_amount: u64, number_of_orders: u64, interval: Duration) -> Self {
+        Self {
+            in_amount,
+            number_of_orders,
+            interval: interval.as_secs(),
+            min_price: None,
+            max_price: None,
+            start_at: None,
+        }
+    }
+}
+
+/// Buy `increment_usdc_value` worth of the output mint every `interval` seconds from a deposit
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceRecurringParams {
+    /// Initial deposit of the input mint, topped up with deposits
+    pub deposit_amount: u64,
+    /// USDC value bought per interval, in atomic units
+    pub increment_usdc_value: u64,
+    /// Seconds between orders
+    pub interval: u64,
+    /// Unix timestamp in seconds, starts right away when not set
+    pub start_at: Option<i64>,
+}
+
+impl PriceRecurringParams {
+    pub fn new(deposit_amount: u64, increment_usdc_value: u64, interval: Duration) -> Self {
+        Self {
+            deposit_amount,
+            increment_usdc_value,
+            interval: interval.as_secs(),
+            start_at: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum RecurringParams {
+    Time(TimeRecurringParams),
+    Price(PriceRecurringParams),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRecurringOrderRequest {
+    #[serde(with = "field_as_string")]
+    pub user: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub input_mint: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub output_mint: Pubkey,
+    pub params: RecurringParams,
+}
+
+/// Unsigned versioned transaction returned by the recurring endpoints
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringTransactionResponse {
+    pub request_id: String,
+    #[serde(with = "base64_serialize_deserialize")]
+    pub transaction: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RecurringType {
+    Time,
+    Price,
+}
+
+/// Which orders [`JupiterSwapApiClient::get_recurring_orders`] lists
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RecurringOrderStatus {
+    Active,
+    /// Completed and cancelled orders
+    History,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringOrder {
+    #[serde(with = "field_as_string")]
+    pub user_pubkey: Pubkey,
+    /// Order account
+    #[serde(with = "field_as_string")]
+    pub order_key: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub input_mint: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub output_mint: Pubkey,
+    #[serde(default)]
+    pub recurring_type: Option<RecurringType>,
+    /// Amounts in atomic units
+    #[serde(default, with = "option_field_as_string")]
+    pub raw_in_deposited: Option<u64>,
+    #[serde(default, with = "option_field_as_string")]
+    pub raw_in_used: Option<u64>,
+    #[serde(default, with = "option_field_as_string")]
+    pub raw_in_withdrawn: Option<u64>,
+    #[serde(default, with = "option_field_as_string")]
+    pub raw_out_received: Option<u64>,
+    #[serde(default, with = "option_field_as_string")]
+    pub raw_out_withdrawn: Option<u64>,
+    /// Only for time based orders
+    #[serde(default, with = "option_field_as_string")]
+    pub raw_in_amount_per_cycle: Option<u64>,
+    /// Seconds between orders
+    #[serde(default, with = "option_field_as_string")]
+    pub cycle_frequency: Option<u64>,
+    /// RFC 3339 timestamps
+    pub created_at: String,
+    pub updated_at: String,
+    pub open_tx: Option<String>,
+    pub close_tx: Option<String>,
+    #[serde(default)]
+    pub user_closed: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecurringOrdersResponse {
+    /// Orders of both types
+    #[serde(rename = "all", default)]
+    pub orders: Vec<RecurringOrder>,
+    /// 1 based
+    pub page: u32,
+    pub total_pages: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelRecurringOrderRequest {
+    #[serde(with = "field_as_string")]
+    pub user: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub order: Pubkey,
+    pub recurring_type: RecurringType,
+}
+
+/// Top up a price based order
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceDepositRequest {
+    #[serde(with = "field_as_string")]
+    pub user: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub order: Pubkey,
+    pub amount: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WithdrawSide {
+    /// Unused input mint
+    In,
+    /// Received output mint
+    Out,
+}
+
+/// Withdraw from a price based order
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceWithdrawRequest {
+    #[serde(with = "field_as_string")]
+    pub user: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub order: Pubkey,
+    pub input_or_output: WithdrawSide,
+    /// Everything when not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<u64>,
+}
+
+impl JupiterSwapApiClient {
+    /// Build the transaction creating a recurring order, to be signed by the user
+    pub async fn create_recurring_order(
+        &self,
+        create_recurring_order_request: &CreateRecurringOrderRequest,
+    ) -> Result<RecurringTransactionResponse, JupiterError> {
+        let interval = match &create_recurring_order_request.params {
+            RecurringParams::Time(params) => params.interval,
+            RecurringParams::Price(params) => params.interval,
+        };
+        if interval == 0 {
+            return Err(JupiterError::InvalidRequest(
+                "interval has to be at least 1 second".to_string(),
+            ));
+        }
+        self.send(
+            Endpoint::RecurringCreateOrder,
+            |api_base_path| {
+                self.client
+                    .post(format!("{api_base_path}/recurring/v1/createOrder"))
+                    .json(create_recurring_order_request)
+            },
+            &RequestOptions::default(),
+        )
+        .await
+        .map(|(recurring_transaction_response, _)| recurring_transaction_response)
+    }
+
+    /// Orders of `wallet` of both types, `page` starts at 1
+    pub async fn get_recurring_orders(
+        &self,
+        wallet: &Pubkey,
+        status: RecurringOrderStatus,
+        page: u32,
+    ) -> Result<RecurringOrdersResponse, JupiterError> {
+        let user = wallet.to_string();
+        self.send(
+            Endpoint::RecurringOrders,
+            |api_base_path| {
+                self.client
+                    .get(format!("{api_base_path}/recurring/v1/getRecurringOrders"))
+                    .query(&[("user", &user)])
+                    .query(&[("orderStatus", status)])
+                    .query(&[("recurringType", "all")])
+                    .query(&[("includeFailedTx", false)])
+                    .query(&[("page", page)])
+            },
+            &RequestOptions::default(),
+        )
+        .await
+        .map(|(recurring_orders_response, _)| recurring_orders_response)
+    }
+
+    /// Build the transaction cancelling an order and returning its remaining funds
+    pub async fn cancel_recurring_order(
+        &self,
+        cancel_recurring_order_request: &CancelRecurringOrderRequest,
+    ) -> Result<RecurringTransactionResponse, JupiterError> {
+        self.send_recurring_update("cancelOrder", cancel_recurring_order_request)
+            .await
+    }
+
+    pub async fn price_deposit(
+        &self,
+        price_deposit_request: &PriceDepositRequest,
+    ) -> Result<RecurringTransactionResponse, JupiterError> {
+        if price_deposit_request.amount == 0 {
+            return Err(JupiterError::InvalidRequest(
+                "amount has to be greater than 0".to_string(),
+            ));
+        }
+        self.send_recurring_update("priceDeposit", price_deposit_request)
+            .await
+    }
+
+    pub async fn price_withdraw(
+        &self,
+        price_withdraw_request: &PriceWithdrawRequest,
+    ) -> Result<RecurringTransactionResponse, JupiterError> {
+        self.send_recurring_update("priceWithdraw", price_withdraw_request)
+            .await
+    }
+
+    async fn send_recurring_update(
+        &self,
+        path: &str,
+        request: &impl Serialize,
+    ) -> Result<RecurringTransactionResponse, JupiterError> {
+        self.send(
+            Endpoint::RecurringUpdateOrder,
+            |api_base_path| {
+                self.client
+                    .post(format!("{api_base_path}/recurring/v1/{path}"))
+                    .json(request)
+            },
+            &RequestOptions::default(),
+        )
+        .await
+        .map(|(recurring_transaction_response, _)| recurring_transaction_response)
+    }
+}
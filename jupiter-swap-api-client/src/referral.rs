@@ -0,0 +1,103 @@
+//! Referral program accounts collecting `platform_fee_bps`
+//!
+//! Fees accrue in referral token accounts, one per referral account and mint, which must exist before
+//! being passed as `fee_account`.
+//!
+//! Only collecting fees is covered. Creating referral token accounts and claiming the collected fees
+//! through the referral endpoints is not supported by this client yet.
+
+use solana_sdk::{pubkey, pubkey::Pubkey};
+
+use crate::{
+    quote::{QuoteRequest, QuoteResponse, SwapMode},
+    swap::SwapRequest,
+    transaction_config::TransactionConfig,
+    JupiterError,
+};
+
+pub const REFERRAL_PROGRAM_ID: Pubkey = pubkey!("REFER4ZgmyYx9c6He5XfaTMiGfdLwRnkV4RPp9t9iF3");
+
+const REFERRAL_ATA_SEED: &[u8] = b"referral_ata";
+
+/// Token account of `referral_account` holding the fees collected in `mint`
+pub fn referral_token_account(referral_account: &Pubkey, mint: &Pubkey) -> Pubkey {
+    referral_token_account_with_bump(referral_account, mint).0
+}
+
+pub fn referral_token_account_with_bump(referral_account: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[REFERRAL_ATA_SEED, referral_account.as_ref(), mint.as_ref()],
+        &REFERRAL_PROGRAM_ID,
+    )
+}
+
+impl TransactionConfig {
+    /// Collect the platform fee into the referral token account of `mint`
+    ///
+    /// `mint` is the input or output mint of the swap, the output mint for ExactIn swaps usually.
+    pub fn with_referral_fee_account(mut self, referral_account: &Pubkey, mint: &Pubkey) -> Self {
+        self.fee_account = Some(referral_token_account(referral_account, mint));
+        self
+    }
+}
+
+/// Where a [`PlatformFeeConfig`] collects fees
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeAccount {
+    /// Token account of the mint the fee is taken in
+    TokenAccount(Pubkey),
+    /// Referral account, the referral token account of the fee mint is derived per swap
+    Referral(Pubkey),
+}
+
+/// Platform fee applied to both the quote and the swap, so that the fee bps and fee account can't disagree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlatformFeeConfig {
+    pub bps: u8,
+    pub fee_account: FeeAccount,
+}
+
+impl PlatformFeeConfig {
+    pub fn new(bps: u8, fee_account: FeeAccount) -> Self {
+        Self { bps, fee_account }
+    }
+
+    pub fn referral(bps: u8, referral_account: Pubkey) -> Self {
+        Self::new(bps, FeeAccount::Referral(referral_account))
+    }
+
+    /// Fee account receiving the fee of `quote_response`, in the output mint for ExactIn and the input mint for ExactOut
+    pub fn fee_account_for(&self, quote_response: &QuoteResponse) -> Pubkey {
+        match self.fee_account {
+            FeeAccount::TokenAccount(token_account) => token_account,
+            FeeAccount::Referral(referral_account) => {
+                let mint = match quote_response.swap_mode {
+                    SwapMode::ExactIn => &quote_response.output_mint,
+                    SwapMode::ExactOut => &quote_response.input_mint,
+                };
+                referral_token_account(&referral_account, mint)
+            }
+        }
+    }
+
+    pub fn apply_to_quote(&self, quote_request: &mut QuoteRequest) {
+        quote_request.platform_fee_bps = Some(self.bps);
+    }
+
+    /// Fails when the quote wasn't requested with the same fee, which would silently lose it
+    pub fn apply_to_swap(&self, swap_request: &mut SwapRequest) -> Result<(), JupiterError> {
+        let quoted_bps = swap_request
+            .quote_response
+            .platform_fee
+            .as_ref()
+            .map(|platform_fee| platform_fee.fee_bps);
+        if quoted_bps != Some(self.bps) {
+            return Err(JupiterError::InvalidRequest(format!(
+                "quote platform fee of {quoted_bps:?} bps doesn't match the configured {} bps",
+                self.bps
+            )));
+        }
+        swap_request.config.fee_account = Some(self.fee_account_for(&swap_request.quote_response));
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+//! Quoting again for an aged quote
+
+use crate::{
+    quote::{QuoteRequest, QuoteResponse, SwapMode},
+    JupiterError, JupiterSwapApiClient,
+};
+
+impl QuoteRequest {
+    /// `self` at the amount and slippage of `quote_response`, keeping dexes and route restrictions
+    ///
+    /// Fails with [`JupiterError::InvalidRequest`] when `quote_response` is for other mints or another swap mode.
+    pub fn refreshed(&self, quote_response: &QuoteResponse) -> Result<QuoteRequest, JupiterError> {
+        if self.input_mint != quote_response.input_mint
+            || self.output_mint != quote_response.output_mint
+            || self.swap_mode.clone().unwrap_or_default() != quote_response.swap_mode
+        {
+            return Err(JupiterError::InvalidRequest(
+                "quote response is not for this quote request".to_string(),
+            ));
+        }
+        let amount = match quote_response.swap_mode {
+            SwapMode::ExactIn => quote_response.in_amount,
+            SwapMode::ExactOut => quote_response.out_amount,
+        };
+        Ok(QuoteRequest {
+            amount,
+            slippage_bps: quote_response.slippage_bps,
+            ..self.clone()
+        })
+    }
+}
+
+/// A quote and its refreshed version, see [`JupiterSwapApiClient::refresh_quote`]
+#[derive(Debug, Clone)]
+pub struct RefreshedQuote {
+    pub previous: QuoteResponse,
+    pub current: QuoteResponse,
+}
+
+impl RefreshedQuote {
+    /// Change of the quoted amount, positive when the new quote is better
+    ///
+    /// The out amount for ExactIn and the in amount for ExactOut, where spending less is better.
+    pub fn amount_drift(&self) -> i128 {
+        match self.current.swap_mode {
+            SwapMode::ExactIn => {
+                i128::from(self.current.out_amount) - i128::from(self.previous.out_amount)
+            }
+            SwapMode::ExactOut => {
+                i128::from(self.previous.in_amount) - i128::from(self.current.in_amount)
+            }
+        }
+    }
+
+    /// [`RefreshedQuote::amount_drift`] in bps of the previous amount
+    pub fn amount_drift_bps(&self) -> Option<i64> {
+        let previous = match self.previous.swap_mode {
+            SwapMode::ExactIn => self.previous.out_amount,
+            SwapMode::ExactOut => self.previous.in_amount,
+        };
+        if previous == 0 {
+            return None;
+        }
+        i64::try_from(self.amount_drift() * 10_000 / i128::from(previous)).ok()
+    }
+
+    /// Slots elapsed between both quotes
+    pub fn slot_drift(&self) -> u64 {
+        self.current
+            .context_slot
+            .saturating_sub(self.previous.context_slot)
+    }
+}
+
+impl JupiterSwapApiClient {
+    /// Quote again the swap of `quote_response`, requested with `quote_request`, see [`QuoteRequest::refreshed`]
+    ///
+    /// The route stays under the same restrictions, so that the drift compares like with like.
+    pub async fn refresh_quote(
+        &self,
+        quote_request: &QuoteRequest,
+        quote_response: &QuoteResponse,
+    ) -> Result<RefreshedQuote, JupiterError> {
+        let current = self
+            .quote(&quote_request.refreshed(quote_response)?)
+            .await?;
+        Ok(RefreshedQuote {
+            previous: quote_response.clone(),
+            current,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+    use solana_sdk::pubkey::Pubkey;
+
+    use super::*;
+    use crate::dex::Dex;
+
+    fn quote_response(quote_request: &QuoteRequest) -> QuoteResponse {
+        QuoteResponse {
+            input_mint: quote_request.input_mint,
+            in_amount: 1_000_000,
+            output_mint: quote_request.output_mint,
+            out_amount: 5_000,
+            other_amount_threshold: 4_970,
+            swap_mode: SwapMode::ExactIn,
+            slippage_bps: 60,
+            computed_auto_slippage: None,
+            uses_quote_minimizing_slippage: None,
+            platform_fee: None,
+            price_impact_pct: Decimal::ZERO,
+            route_plan: Vec::new(),
+            context_slot: 0,
+            time_taken: 0.0,
+        }
+    }
+
+    #[test]
+    fn refreshed_keeps_route_restrictions() {
+        let quote_request =
+            QuoteRequest::builder(Pubkey::new_unique(), Pubkey::new_unique(), 2_000_000)
+                .dexes([Dex::Whirlpool])
+                .only_direct_routes(true)
+                .max_accounts(32)
+                .build()
+                .unwrap();
+        let refreshed = quote_request
+            .refreshed(&quote_response(&quote_request))
+            .unwrap();
+        assert_eq!(refreshed.amount, 1_000_000);
+        assert_eq!(refreshed.slippage_bps, 60);
+        assert_eq!(refreshed.dexes, quote_request.dexes);
+        assert_eq!(refreshed.only_direct_routes, Some(true));
+        assert_eq!(refreshed.max_accounts, Some(32));
+    }
+
+    #[test]
+    fn refreshed_rejects_other_swaps() {
+        let quote_request =
+            QuoteRequest::builder(Pubkey::new_unique(), Pubkey::new_unique(), 1_000_000)
+                .build()
+                .unwrap();
+        let mut quote_response = quote_response(&quote_request);
+        quote_response.output_mint = Pubkey::new_unique();
+        assert!(quote_request.refreshed(&quote_response).is_err());
+    }
+}
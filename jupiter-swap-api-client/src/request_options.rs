@@ -0,0 +1,248 @@
+use std::{collections::HashMap, fmt, time::Duration};
+
+use reqwest::{header::HeaderMap, Method, Request};
+
+/// Endpoints called by the client
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    Quote,
+    Swap,
+    SwapInstructions,
+    ProgramIdToLabel,
+    IndexedRouteMap,
+    Markets,
+    Health,
+    Price,
+    Tokens,
+    TriggerCreateOrder,
+    TriggerCancelOrder,
+    TriggerOrders,
+    RecurringCreateOrder,
+    RecurringOrders,
+    /// Cancel, deposit and withdraw
+    RecurringUpdateOrder,
+    UltraOrder,
+    UltraExecute,
+    UltraBalances,
+    UltraShield,
+    UltraRouters,
+    /// Deposit and withdraw
+    LendEarn,
+    LendPositions,
+}
+
+impl Endpoint {
+    pub fn is_post(self) -> bool {
+        match self {
+            Endpoint::Quote
+            | Endpoint::ProgramIdToLabel
+            | Endpoint::IndexedRouteMap
+            | Endpoint::Health
+            | Endpoint::Price
+            | Endpoint::Tokens
+            | Endpoint::TriggerOrders
+            | Endpoint::RecurringOrders
+            | Endpoint::UltraOrder
+            | Endpoint::UltraBalances
+            | Endpoint::UltraShield
+            | Endpoint::UltraRouters
+            | Endpoint::LendPositions => false,
+            Endpoint::Swap
+            | Endpoint::SwapInstructions
+            | Endpoint::Markets
+            | Endpoint::TriggerCreateOrder
+            | Endpoint::TriggerCancelOrder
+            | Endpoint::RecurringCreateOrder
+            | Endpoint::RecurringUpdateOrder
+            | Endpoint::UltraExecute
+            | Endpoint::LendEarn => true,
+        }
+    }
+
+    /// Served under the swap API base path, the others under the API base path
+    pub fn is_swap_api(self) -> bool {
+        match self {
+            Endpoint::Quote
+            | Endpoint::Swap
+            | Endpoint::SwapInstructions
+            | Endpoint::ProgramIdToLabel
+            | Endpoint::IndexedRouteMap
+            | Endpoint::Markets
+            | Endpoint::Health => true,
+            Endpoint::Price
+            | Endpoint::Tokens
+            | Endpoint::TriggerCreateOrder
+            | Endpoint::TriggerCancelOrder
+            | Endpoint::TriggerOrders
+            | Endpoint::RecurringCreateOrder
+            | Endpoint::RecurringOrders
+            | Endpoint::RecurringUpdateOrder
+            | Endpoint::UltraOrder
+            | Endpoint::UltraExecute
+            | Endpoint::UltraBalances
+            | Endpoint::UltraShield
+            | Endpoint::UltraRouters
+            | Endpoint::LendEarn
+            | Endpoint::LendPositions => false,
+        }
+    }
+
+    /// Body size limit the endpoint needs, used when [`JupiterSwapApiClient::max_response_body_size`](crate::JupiterSwapApiClient::max_response_body_size) is lower
+    pub fn min_response_body_size_limit(self) -> usize {
+        match self {
+            // The full token list alone is far above the default limit
+            Endpoint::Tokens => TOKENS_MAX_RESPONSE_BODY_SIZE,
+            _ => 0,
+        }
+    }
+
+    /// Successful responses with an `error` describe a failure in the typed response, not an API error
+    pub(crate) fn reports_errors_in_response(self) -> bool {
+        matches!(self, Endpoint::UltraExecute)
+    }
+}
+
+/// Body size limit of [`Endpoint::Tokens`], room for a growing full token list
+pub const TOKENS_MAX_RESPONSE_BODY_SIZE: usize = 256 * 1024 * 1024;
+
+/// Timeouts applied per endpoint, `None` falls back to the timeout of the underlying `reqwest::Client`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EndpointTimeouts {
+    pub quote: Option<Duration>,
+    pub swap: Option<Duration>,
+    pub swap_instructions: Option<Duration>,
+}
+
+impl EndpointTimeouts {
+    pub fn get(&self, endpoint: Endpoint) -> Option<Duration> {
+        match endpoint {
+            Endpoint::Quote => self.quote,
+            Endpoint::Swap => self.swap,
+            Endpoint::SwapInstructions => self.swap_instructions,
+            // Other APIs use the timeout of the underlying `reqwest::Client`
+            _ => None,
+        }
+    }
+}
+
+/// How response fields unknown to this client are handled
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DeserializationMode {
+    /// Ignore unknown fields, so that additions to the API don't break deployed clients
+    #[default]
+    Lenient,
+    /// Fail with [`JupiterError::UnknownFields`](crate::JupiterError::UnknownFields), to catch API drift in CI
+    Strict,
+}
+
+/// Settings for a single call, taking precedence over the client configuration
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestOptions {
+    /// Overrides the endpoint timeout
+    pub timeout: Option<Duration>,
+    /// Overrides the client API key, e.g. to serve several tenants from one client
+    pub api_key: Option<String>,
+    /// Attached after the client default headers, replacing the ones with the same name
+    pub headers: HeaderMap,
+    /// Appended after the client default query parameters
+    pub query_params: HashMap<String, String>,
+    /// Sent as the [`IDEMPOTENCY_KEY_HEADER`] on every attempt of this call, including retries
+    pub idempotency_key: Option<String>,
+}
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Random 128 bit key, hex encoded
+pub fn generate_idempotency_key() -> String {
+    format!("{:032x}", rand::random::<u128>())
+}
+
+/// Method and final URL of a request, with sensitive query parameters redacted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestContext {
+    pub endpoint: Endpoint,
+    pub method: Method,
+    /// Including the query string, ready to be replayed with curl
+    pub url: String,
+}
+
+const REDACTED: &str = "REDACTED";
+
+/// Compared case insensitively to whole names, so that e.g. `restrictIntermediateTokens` is kept
+const SENSITIVE_QUERY_PARAMS: &[&str] = &[
+    "key",
+    "api-key",
+    "api_key",
+    "apikey",
+    "token",
+    "access_token",
+    "secret",
+    "password",
+    "signature",
+];
+
+fn is_sensitive(query_param: &str) -> bool {
+    SENSITIVE_QUERY_PARAMS
+        .iter()
+        .any(|sensitive| query_param.eq_ignore_ascii_case(sensitive))
+}
+
+impl RequestContext {
+    pub fn new(endpoint: Endpoint, request: &Request) -> Self {
+        let mut url = request.url().clone();
+        if url.query_pairs().any(|(key, _)| is_sensitive(&key)) {
+            let query_pairs = url
+                .query_pairs()
+                .map(|(key, value)| {
+                    let value = if is_sensitive(&key) {
+                        REDACTED.to_string()
+                    } else {
+                        value.into_owned()
+                    };
+                    (key.into_owned(), value)
+                })
+                .collect::<Vec<_>>();
+            url.query_pairs_mut().clear().extend_pairs(query_pairs);
+        }
+        Self {
+            endpoint,
+            method: request.method().clone(),
+            url: url.into(),
+        }
+    }
+}
+
+impl fmt::Display for RequestContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.method, self.url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::{Client, Url};
+
+    use super::*;
+
+    fn context(url: &str) -> RequestContext {
+        let request = Client::new().get(Url::parse(url).unwrap()).build().unwrap();
+        RequestContext::new(Endpoint::Quote, &request)
+    }
+
+    #[test]
+    fn redacts_secrets() {
+        let context =
+            context("https://api.jup.ag/swap/v1/quote?amount=1&apiKey=secret&Token=secret");
+        assert_eq!(
+            context.url,
+            "https://api.jup.ag/swap/v1/quote?amount=1&apiKey=REDACTED&Token=REDACTED"
+        );
+    }
+
+    #[test]
+    fn keeps_quote_params() {
+        let url = "https://api.jup.ag/swap/v1/quote?amount=1&restrictIntermediateTokens=true\
+            &tokenCategoryBasedIntermediateTokens=true&maxAccounts=64";
+        assert_eq!(context(url).url, url);
+    }
+}
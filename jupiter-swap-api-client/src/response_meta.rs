@@ -0,0 +1,35 @@
+use std::time::Duration;
+
+use reqwest::{header::HeaderMap, StatusCode};
+
+/// Headers identifying a request to Jupiter support, in order of preference
+const REQUEST_ID_HEADERS: [&str; 3] = ["x-request-id", "x-amzn-requestid", "cf-ray"];
+
+/// Details of the HTTP response that produced a result
+#[derive(Debug, Clone)]
+pub struct ResponseMeta {
+    pub status_code: StatusCode,
+    pub headers: HeaderMap,
+    /// Round trip of the successful attempt, from sending the request to reading the whole body
+    pub latency: Duration,
+    /// Base path that served the request, differs from the client base path after a failover
+    pub base_path: String,
+    /// Number of attempts including retries
+    pub attempts: u32,
+}
+
+impl ResponseMeta {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name)?.to_str().ok()
+    }
+
+    /// Id to quote when contacting Jupiter support
+    pub fn request_id(&self) -> Option<&str> {
+        REQUEST_ID_HEADERS.iter().find_map(|name| self.header(name))
+    }
+
+    /// `server-timing` reported by the upstream, e.g. `total;dur=12`
+    pub fn server_timing(&self) -> Option<&str> {
+        self.header("server-timing")
+    }
+}
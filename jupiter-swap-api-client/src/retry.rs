@@ -0,0 +1,242 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rand::Rng;
+use reqwest::header::HeaderMap;
+
+use crate::JupiterError;
+
+/// Retry transient failures with exponential backoff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled on every following retry
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Randomize each delay between half and the full backoff to avoid synchronized retries
+    pub jitter: bool,
+    /// Also retry when no route could be found, which can be transient right after a market got listed
+    pub retry_on_no_route: bool,
+    /// Retry rate limited requests, waiting at least as long as the server asked for
+    ///
+    /// Requests the server asks to wait longer than `max_delay` for are not retried.
+    pub retry_on_rate_limit: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(2),
+            jitter: true,
+            retry_on_no_route: false,
+            retry_on_rate_limit: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn should_retry(&self, error: &JupiterError) -> bool {
+        if let JupiterError::RateLimited { retry_after, .. } = error {
+            self.retry_on_rate_limit
+                && retry_after.map_or(true, |retry_after| retry_after <= self.max_delay)
+        } else if error.is_no_route() {
+            self.retry_on_no_route
+        } else {
+            error.is_retryable()
+        }
+    }
+
+    /// Delay to wait after `error` on the given attempt, honoring the server's `Retry-After` up to `max_delay`
+    pub fn delay_for(&self, error: &JupiterError, attempt: u32) -> Duration {
+        let delay = self.delay(attempt);
+        match error {
+            JupiterError::RateLimited {
+                retry_after: Some(retry_after),
+                ..
+            } => delay.max(*retry_after).min(self.max_delay),
+            _ => delay,
+        }
+    }
+
+    /// Delay to wait after the given failed attempt, starting at 1
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_delay);
+        if self.jitter {
+            let half = backoff / 2;
+            half + half.mul_f64(rand::thread_rng().gen::<f64>())
+        } else {
+            backoff
+        }
+    }
+}
+
+/// `x-ratelimit-reset` values from this one on are Unix timestamps rather than delays, i.e. after September 2001
+const MIN_RATE_LIMIT_RESET_TIMESTAMP: u64 = 1_000_000_000;
+
+/// Parses `Retry-After` as delay seconds or an IMF-fixdate HTTP date, falling back to `x-ratelimit-reset`
+///
+/// `x-ratelimit-reset` is either a delay in seconds or a Unix timestamp. Obsolete HTTP date formats are ignored.
+pub(crate) fn retry_after(headers: &HeaderMap, now: SystemTime) -> Option<Duration> {
+    let header = |name: &str| headers.get(name)?.to_str().ok().map(str::trim);
+    let until = |timestamp: u64| {
+        let now = now.duration_since(UNIX_EPOCH).unwrap_or_default();
+        Duration::from_secs(timestamp).saturating_sub(now)
+    };
+    let retry_after =
+        header("retry-after").and_then(|retry_after| match retry_after.parse::<u64>() {
+            Ok(seconds) => Some(Duration::from_secs(seconds)),
+            Err(_) => http_date_timestamp(retry_after).map(until),
+        });
+    retry_after.or_else(|| {
+        let reset = header("x-ratelimit-reset")?.parse::<u64>().ok()?;
+        Some(if reset >= MIN_RATE_LIMIT_RESET_TIMESTAMP {
+            until(reset)
+        } else {
+            Duration::from_secs(reset)
+        })
+    })
+}
+
+/// Unix timestamp of an IMF-fixdate, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`
+fn http_date_timestamp(date: &str) -> Option<u64> {
+    let mut parts = date.split_whitespace();
+    let (_weekday, day, month, year, time, zone) = (
+        parts.next()?,
+        parts.next()?,
+        parts.next()?,
+        parts.next()?,
+        parts.next()?,
+        parts.next()?,
+    );
+    if zone != "GMT" || parts.next().is_some() {
+        return None;
+    }
+    let day = day
+        .parse::<u64>()
+        .ok()
+        .filter(|day| (1..=31).contains(day))?;
+    let month = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ]
+    .iter()
+    .position(|name| *name == month)? as u64
+        + 1;
+    let year = year.parse::<u64>().ok().filter(|year| *year >= 1970)?;
+    let mut time = time.split(':').map(|part| part.parse::<u64>().ok());
+    let (hours, minutes, seconds) = (time.next()??, time.next()??, time.next()??);
+    if time.next().is_some() || hours > 23 || minutes > 59 || seconds > 60 {
+        return None;
+    }
+    // Days from the civil date, shifting the year to start in March so that leap days come last
+    let (year, month) = if month <= 2 {
+        (year - 1, month + 9)
+    } else {
+        (year, month - 3)
+    };
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+    Some(days * 86_400 + hours * 3_600 + minutes * 60 + seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    fn headers(name: &'static str, value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(name, HeaderValue::from_static(value));
+        headers
+    }
+
+    fn at(timestamp: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(timestamp)
+    }
+
+    #[test]
+    fn retry_after_seconds() {
+        assert_eq!(
+            retry_after(&headers("retry-after", "3"), at(0)),
+            Some(Duration::from_secs(3))
+        );
+    }
+
+    #[test]
+    fn retry_after_http_date() {
+        assert_eq!(
+            http_date_timestamp("Sun, 06 Nov 1994 08:49:37 GMT"),
+            Some(784_111_777)
+        );
+        assert_eq!(
+            retry_after(
+                &headers("retry-after", "Sun, 06 Nov 1994 08:49:37 GMT"),
+                at(784_111_770)
+            ),
+            Some(Duration::from_secs(7))
+        );
+        assert_eq!(
+            retry_after(
+                &headers("retry-after", "Sun, 06 Nov 1994 08:49:37 GMT"),
+                at(784_111_800)
+            ),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(
+            retry_after(
+                &headers("retry-after", "Sunday, 06-Nov-94 08:49:37 GMT"),
+                at(0)
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn rate_limit_reset_delay_or_timestamp() {
+        assert_eq!(
+            retry_after(&headers("x-ratelimit-reset", "10"), at(1_700_000_000)),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(
+            retry_after(
+                &headers("x-ratelimit-reset", "1700000005"),
+                at(1_700_000_000)
+            ),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(
+            retry_after(
+                &headers("x-ratelimit-reset", "1699999990"),
+                at(1_700_000_000)
+            ),
+            Some(Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn long_retry_after_is_not_retried() {
+        let policy = RetryPolicy {
+            jitter: false,
+            ..RetryPolicy::default()
+        };
+        let rate_limited = |retry_after: u64| JupiterError::RateLimited {
+            retry_after: Some(Duration::from_secs(retry_after)),
+            request: None,
+        };
+        assert!(policy.should_retry(&rate_limited(1)));
+        assert_eq!(
+            policy.delay_for(&rate_limited(1), 1),
+            Duration::from_secs(1)
+        );
+        assert!(!policy.should_retry(&rate_limited(3_600)));
+        assert_eq!(policy.delay_for(&rate_limited(3_600), 1), policy.max_delay);
+    }
+}
@@ -0,0 +1,108 @@
+//! Indexed route map, to know which pairs are routable without quoting
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    request_options::{Endpoint, RequestOptions},
+    swap::parse_pubkey,
+    JupiterError, JupiterSwapApiClient,
+};
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct IndexedRouteMapInternal {
+    mint_keys: Vec<String>,
+    /// Index of an input mint in `mint_keys` to the indexes of its output mints
+    indexed_route_map: HashMap<String, Vec<usize>>,
+}
+
+/// Output mints routable from every input mint
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RouteMap {
+    routes: HashMap<Pubkey, HashSet<Pubkey>>,
+}
+
+impl RouteMap {
+    pub fn is_routable(&self, input_mint: &Pubkey, output_mint: &Pubkey) -> bool {
+        self.routes
+            .get(input_mint)
+            .is_some_and(|output_mints| output_mints.contains(output_mint))
+    }
+
+    pub fn output_mints(&self, input_mint: &Pubkey) -> impl Iterator<Item = &Pubkey> {
+        self.routes.get(input_mint).into_iter().flatten()
+    }
+
+    pub fn input_mints(&self) -> impl Iterator<Item = &Pubkey> {
+        self.routes.keys()
+    }
+
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+}
+
+impl TryFrom<IndexedRouteMapInternal> for RouteMap {
+    type Error = JupiterError;
+
+    fn try_from(value: IndexedRouteMapInternal) -> Result<Self, Self::Error> {
+        let mints = value
+            .mint_keys
+            .iter()
+            .map(|mint| parse_pubkey(mint, "route map mint"))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mint = |index: usize| {
+            mints.get(index).copied().ok_or_else(|| {
+                JupiterError::MalformedResponse(format!(
+                    "route map mint index {index} out of range"
+                ))
+            })
+        };
+        let routes = value
+            .indexed_route_map
+            .iter()
+            .map(|(input_index, output_indexes)| {
+                let input_index = input_index.parse::<usize>().map_err(|e| {
+                    JupiterError::MalformedResponse(format!(
+                        "invalid route map index {input_index}: {e}"
+                    ))
+                })?;
+                let output_mints = output_indexes
+                    .iter()
+                    .map(|output_index| mint(*output_index))
+                    .collect::<Result<_, _>>()?;
+                Ok((mint(input_index)?, output_mints))
+            })
+            .collect::<Result<_, JupiterError>>()?;
+        Ok(Self { routes })
+    }
+}
+
+impl JupiterSwapApiClient {
+    /// Routable pairs, only served by the [`ApiVersion::V6`](crate::api_version::ApiVersion::V6) layout, e.g. self-hosted
+    pub async fn indexed_route_map(
+        &self,
+        only_direct_routes: bool,
+    ) -> Result<RouteMap, JupiterError> {
+        let path = self.swap_api_path(Endpoint::IndexedRouteMap)?;
+        let (route_map, _) = self
+            .send::<IndexedRouteMapInternal>(
+                Endpoint::IndexedRouteMap,
+                |base_path| {
+                    self.client
+                        .get(format!("{base_path}{path}"))
+                        .query(&[("onlyDirectRoutes", only_direct_routes)])
+                },
+                &RequestOptions::default(),
+            )
+            .await?;
+        route_map.try_into()
+    }
+}
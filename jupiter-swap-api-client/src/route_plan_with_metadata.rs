@@ -1,7 +1,9 @@
+use std::{collections::HashMap, fmt};
+
 use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
 
-use crate::serde_helpers::field_as_string;
+use crate::{quote::QuoteResponse, serde_helpers::field_as_string};
 
 /// Topologically sorted DAG with additional metadata for rendering
 pub type RoutePlanWithMetadata = Vec<RoutePlanStep>;
@@ -34,3 +36,80 @@ pub struct SwapInfo {
     #[serde(with = "field_as_string")]
     pub fee_mint: Pubkey,
 }
+
+/// Introspection of a route plan, implemented for [`RoutePlanWithMetadata`]
+pub trait RoutePlan {
+    /// Number of swaps, split steps counting individually
+    fn hops(&self) -> usize;
+    /// Distinct dex labels in route order
+    fn dexes_used(&self) -> Vec<&str>;
+    /// Percent of its input mint each step swaps, in route order
+    fn split_percentages(&self) -> Vec<u8>;
+    /// Fees summed per fee mint, saturating
+    fn total_fees_by_mint(&self) -> HashMap<Pubkey, u64>;
+}
+
+impl RoutePlan for [RoutePlanStep] {
+    fn hops(&self) -> usize {
+        self.len()
+    }
+
+    fn dexes_used(&self) -> Vec<&str> {
+        let mut labels = Vec::new();
+        for step in self {
+            if !labels.contains(&step.swap_info.label.as_str()) {
+                labels.push(step.swap_info.label.as_str());
+            }
+        }
+        labels
+    }
+
+    fn split_percentages(&self) -> Vec<u8> {
+        self.iter().map(|step| step.percent).collect()
+    }
+
+    fn total_fees_by_mint(&self) -> HashMap<Pubkey, u64> {
+        let mut fees = HashMap::new();
+        for step in self {
+            let fee = fees.entry(step.swap_info.fee_mint).or_insert(0u64);
+            *fee = fee.saturating_add(step.swap_info.fee_amount);
+        }
+        fees
+    }
+}
+
+/// Human readable route, e.g. `100% So11..1112 -> EPjF..Dt1v via Whirlpool`
+pub struct RouteSummary<'a>(pub &'a [RoutePlanStep]);
+
+fn short_pubkey(pubkey: &Pubkey) -> String {
+    let pubkey = pubkey.to_string();
+    if pubkey.len() <= 8 {
+        return pubkey;
+    }
+    format!("{}..{}", &pubkey[..4], &pubkey[pubkey.len() - 4..])
+}
+
+impl fmt::Display for RouteSummary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, step) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(
+                f,
+                "{}% {} -> {} via {}",
+                step.percent,
+                short_pubkey(&step.swap_info.input_mint),
+                short_pubkey(&step.swap_info.output_mint),
+                step.swap_info.label
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl QuoteResponse {
+    pub fn route_summary(&self) -> RouteSummary<'_> {
+        RouteSummary(&self.route_plan)
+    }
+}
@@ -0,0 +1,66 @@
+use std::{
+    pin::pin,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use tokio::sync::Notify;
+
+/// Tracks in-flight calls shared by all clones of a client
+#[derive(Debug, Default)]
+pub(crate) struct InFlight {
+    closed: AtomicBool,
+    count: AtomicUsize,
+    idle: Notify,
+}
+
+/// Held for the duration of a call, including its retries
+pub(crate) struct InFlightGuard(Arc<InFlight>);
+
+impl InFlight {
+    /// `None` once the client is shut down
+    pub(crate) fn enter(self: &Arc<Self>) -> Option<InFlightGuard> {
+        self.count.fetch_add(1, Ordering::SeqCst);
+        let guard = InFlightGuard(self.clone());
+        // Checked after counting the call so that `drain` can't miss it
+        (!self.closed.load(Ordering::SeqCst)).then_some(guard)
+    }
+
+    pub(crate) fn count(&self) -> usize {
+        self.count.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    /// Returns false if calls were still in flight after `timeout`
+    pub(crate) async fn drain(&self, timeout: Duration) -> bool {
+        let drained = async {
+            loop {
+                let mut idle = pin!(self.idle.notified());
+                idle.as_mut().enable();
+                if self.count() == 0 {
+                    return;
+                }
+                idle.await;
+            }
+        };
+        tokio::time::timeout(timeout, drained).await.is_ok()
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        if self.0.count.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.idle.notify_waiters();
+        }
+    }
+}
@@ -0,0 +1,34 @@
+//! Signing abstraction over local keypairs and remote signers, e.g. hardware wallets or KMS backed keys
+
+use std::{future::Future, pin::Pin};
+
+use solana_sdk::{pubkey::Pubkey, signature::Signature, signer::Signer};
+
+use crate::JupiterError;
+
+pub type SignFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Signature, JupiterError>> + Send + 'a>>;
+
+/// Signs serialized transaction messages, boxing the future to stay usable as `dyn TransactionSigner`
+///
+/// Implemented for every [`Signer`], remote signers implement it directly. Methods are named apart from [`Signer`]
+/// so that both traits can be in scope.
+pub trait TransactionSigner: Send + Sync {
+    fn public_key(&self) -> Pubkey;
+
+    /// Signature of the serialized `message`
+    fn sign<'a>(&'a self, message: &'a [u8]) -> SignFuture<'a>;
+}
+
+impl<T: Signer + Send + Sync + ?Sized> TransactionSigner for T {
+    fn public_key(&self) -> Pubkey {
+        self.pubkey()
+    }
+
+    fn sign<'a>(&'a self, message: &'a [u8]) -> SignFuture<'a> {
+        let signature = self
+            .try_sign_message(message)
+            .map_err(|e| JupiterError::Signing(e.to_string()));
+        Box::pin(std::future::ready(signature))
+    }
+}
@@ -0,0 +1,176 @@
+//! Simulating a swap transaction through an RPC node before sending it
+
+use solana_account_decoder::{UiAccount, UiAccountEncoding};
+use solana_client::{
+    nonblocking::rpc_client::RpcClient,
+    rpc_config::{RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig},
+};
+use solana_sdk::{
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    transaction::{TransactionError, VersionedTransaction},
+};
+
+use crate::{
+    swap::{SwapResponse, SLIPPAGE_TOLERANCE_EXCEEDED_ERROR},
+    JupiterError,
+};
+
+/// Offset of the `u64` amount in token and token 2022 accounts, after the mint and the owner
+const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
+
+/// Balance change of a token account over the simulated transaction, a missing account counting as 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenBalanceDelta {
+    pub token_account: Pubkey,
+    pub pre_amount: u64,
+    pub post_amount: u64,
+}
+
+impl TokenBalanceDelta {
+    pub fn delta(&self) -> i128 {
+        i128::from(self.post_amount) - i128::from(self.pre_amount)
+    }
+}
+
+/// Common causes of failed swap simulations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimulationFailure {
+    /// The price moved since quoting, quote again
+    SlippageToleranceExceeded,
+    /// Not enough lamports for the fees and rent, or not enough input tokens
+    InsufficientFunds,
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapSimulation {
+    pub error: Option<TransactionError>,
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+    /// Empty when the simulation failed, the RPC node not returning accounts
+    pub token_balance_deltas: Vec<TokenBalanceDelta>,
+}
+
+impl SwapSimulation {
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// `None` when the simulation succeeded
+    pub fn failure(&self) -> Option<SimulationFailure> {
+        let error = self.error.as_ref()?;
+        let failure = match error {
+            TransactionError::InstructionError(
+                _,
+                InstructionError::Custom(SLIPPAGE_TOLERANCE_EXCEEDED_ERROR),
+            ) => SimulationFailure::SlippageToleranceExceeded,
+            TransactionError::InsufficientFundsForFee
+            | TransactionError::InsufficientFundsForRent { .. }
+            | TransactionError::InstructionError(_, InstructionError::InsufficientFunds) => {
+                SimulationFailure::InsufficientFunds
+            }
+            // The token and system programs log their insufficient funds errors
+            _ if self.logs.iter().any(|log| {
+                let log = log.to_ascii_lowercase();
+                log.contains("insufficient funds") || log.contains("insufficient lamports")
+            }) =>
+            {
+                SimulationFailure::InsufficientFunds
+            }
+            _ => SimulationFailure::Other,
+        };
+        Some(failure)
+    }
+}
+
+fn token_amount(token_account: &Pubkey, data: &[u8]) -> Result<u64, JupiterError> {
+    data.get(TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8)
+        .and_then(|amount| amount.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or_else(|| {
+            JupiterError::MalformedResponse(format!("{token_account} is not a token account"))
+        })
+}
+
+fn simulated_token_amount(
+    token_account: &Pubkey,
+    account: Option<&UiAccount>,
+) -> Result<u64, JupiterError> {
+    let Some(account) = account else {
+        return Ok(0);
+    };
+    let data = account.data.decode().ok_or_else(|| {
+        JupiterError::MalformedResponse(format!("undecodable simulated account {token_account}"))
+    })?;
+    token_amount(token_account, &data)
+}
+
+/// Simulates `transaction` without verifying signatures and with the latest blockhash, so it can be unsigned
+///
+/// `token_accounts` are the accounts to report balance changes of, e.g. the input and output token accounts.
+/// Their balances before the swap are fetched right before simulating, so they can be off if they change in between.
+pub async fn simulate_swap(
+    rpc_client: &RpcClient,
+    transaction: &VersionedTransaction,
+    token_accounts: &[Pubkey],
+) -> Result<SwapSimulation, JupiterError> {
+    let pre_accounts = if token_accounts.is_empty() {
+        Vec::new()
+    } else {
+        rpc_client.get_multiple_accounts(token_accounts).await?
+    };
+    let result = rpc_client
+        .simulate_transaction_with_config(
+            transaction,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: true,
+                accounts: (!token_accounts.is_empty()).then(|| {
+                    RpcSimulateTransactionAccountsConfig {
+                        encoding: Some(UiAccountEncoding::Base64),
+                        addresses: token_accounts.iter().map(Pubkey::to_string).collect(),
+                    }
+                }),
+                ..RpcSimulateTransactionConfig::default()
+            },
+        )
+        .await?
+        .value;
+    let token_balance_deltas = match &result.accounts {
+        Some(post_accounts) if result.err.is_none() => token_accounts
+            .iter()
+            .zip(&pre_accounts)
+            .enumerate()
+            .map(|(index, (token_account, pre_account))| {
+                let pre_amount = pre_account
+                    .as_ref()
+                    .map_or(Ok(0), |account| token_amount(token_account, &account.data))?;
+                let post_account = post_accounts.get(index).and_then(Option::as_ref);
+                Ok(TokenBalanceDelta {
+                    token_account: *token_account,
+                    pre_amount,
+                    post_amount: simulated_token_amount(token_account, post_account)?,
+                })
+            })
+            .collect::<Result<_, JupiterError>>()?,
+        _ => Vec::new(),
+    };
+    Ok(SwapSimulation {
+        error: result.err,
+        logs: result.logs.unwrap_or_default(),
+        units_consumed: result.units_consumed,
+        token_balance_deltas,
+    })
+}
+
+impl SwapResponse {
+    /// [`simulate_swap`] of [`SwapResponse::versioned_transaction`]
+    pub async fn simulate(
+        &self,
+        rpc_client: &RpcClient,
+        token_accounts: &[Pubkey],
+    ) -> Result<SwapSimulation, JupiterError> {
+        simulate_swap(rpc_client, &self.versioned_transaction()?, token_accounts).await
+    }
+}
@@ -0,0 +1,81 @@
+//! Slippage settings shared by quote and swap requests
+
+use crate::{
+    quote::{QuoteRequest, MAX_BPS},
+    swap::SwapRequest,
+    transaction_config::{DynamicSlippageSettings, TransactionConfig},
+    JupiterError,
+};
+
+/// Sets `slippageBps` and `dynamicSlippage` together so they can't disagree
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Slippage {
+    Bps(u16),
+    /// e.g. `0.5` for 50 bps, rounded to the nearest basis point
+    Percent(f64),
+    /// Let the API estimate the slippage at swap time, up to `max_bps`
+    Dynamic {
+        max_bps: u16,
+    },
+}
+
+impl Slippage {
+    /// Fixed slippage, or the upper bound of dynamic slippage
+    pub fn max_bps(&self) -> Result<u16, JupiterError> {
+        let bps = match *self {
+            Slippage::Bps(bps) | Slippage::Dynamic { max_bps: bps } => bps,
+            Slippage::Percent(percent) => {
+                let bps = (percent * 100.0).round();
+                if !(0.0..=f64::from(MAX_BPS)).contains(&bps) {
+                    return Err(JupiterError::InvalidRequest(format!(
+                        "slippage of {percent}% has to be between 0% and 100%"
+                    )));
+                }
+                bps as u16
+            }
+        };
+        if bps > MAX_BPS {
+            return Err(JupiterError::InvalidRequest(
+                "slippage can't exceed 10000 bps".to_string(),
+            ));
+        }
+        Ok(bps)
+    }
+
+    pub fn is_dynamic(&self) -> bool {
+        matches!(self, Slippage::Dynamic { .. })
+    }
+}
+
+impl From<u16> for Slippage {
+    fn from(bps: u16) -> Self {
+        Slippage::Bps(bps)
+    }
+}
+
+impl QuoteRequest {
+    /// Dynamic slippage quotes with `max_bps` as `slippage_bps`
+    pub fn set_slippage(&mut self, slippage: Slippage) -> Result<(), JupiterError> {
+        self.slippage_bps = slippage.max_bps()?;
+        self.dynamic_slippage = slippage.is_dynamic().then_some(true);
+        Ok(())
+    }
+}
+
+impl TransactionConfig {
+    pub fn set_slippage(&mut self, slippage: Slippage) -> Result<(), JupiterError> {
+        let max_bps = slippage.max_bps()?;
+        self.dynamic_slippage = slippage.is_dynamic().then_some(DynamicSlippageSettings {
+            min_bps: None,
+            max_bps: Some(max_bps),
+        });
+        Ok(())
+    }
+}
+
+impl SwapRequest {
+    /// Fixed slippage is taken from the quote, pass the slippage of the [`QuoteRequest`]
+    pub fn set_slippage(&mut self, slippage: Slippage) -> Result<(), JupiterError> {
+        self.config.set_slippage(slippage)
+    }
+}
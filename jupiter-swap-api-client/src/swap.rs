@@ -1,11 +1,27 @@
 use crate::{
-    quote::QuoteResponse, serde_helpers::field_as_string, transaction_config::TransactionConfig,
+    quote::{QuoteResponse, SwapMode},
+    referral::PlatformFeeConfig,
+    serde_helpers::field_as_string,
+    slippage::Slippage,
+    token_account::{associated_token_address, is_associated_token_address},
+    transaction_config::{
+        ComputeUnitPriceMicroLamports, PrioritizationFeeLamports, TransactionConfig,
+    },
+    JupiterError,
 };
+use base64::{engine::general_purpose::STANDARD, Engine};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use solana_sdk::{
+    hash::{Hash, HASH_BYTES},
     instruction::{AccountMeta, Instruction},
+    message::VersionedMessage,
     pubkey::Pubkey,
+    transaction::{Transaction, VersionedTransaction},
+};
+use std::{
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -18,6 +34,181 @@ pub struct SwapRequest {
     pub config: TransactionConfig,
 }
 
+impl SwapRequest {
+    /// Same as [`QuoteResponse::into_swap_request`], start from a preset with [`SwapRequestBuilder::config`]
+    pub fn builder(user_public_key: Pubkey, quote_response: QuoteResponse) -> SwapRequestBuilder {
+        quote_response.into_swap_request(user_public_key)
+    }
+
+    /// Catch obvious mistakes locally instead of spending rate limit quota on them
+    pub fn validate(&self) -> Result<(), JupiterError> {
+        if self.user_public_key == Pubkey::default() {
+            return Err(JupiterError::InvalidRequest(
+                "user_public_key has to be set".to_string(),
+            ));
+        }
+        if self.config.destination_token_account == Some(self.user_public_key) {
+            return Err(JupiterError::InvalidRequest(
+                "destination_token_account is the user wallet, pass a token account".to_string(),
+            ));
+        }
+        if self.config.fee_account == Some(self.user_public_key) {
+            return Err(JupiterError::InvalidRequest(
+                "fee_account is the user wallet, pass a token account".to_string(),
+            ));
+        }
+        if self
+            .config
+            .destination_token_account
+            .is_some_and(|destination_token_account| {
+                is_associated_token_address(
+                    &destination_token_account,
+                    &self.user_public_key,
+                    &self.quote_response.output_mint,
+                )
+            })
+        {
+            return Err(JupiterError::InvalidRequest(
+                "destination_token_account is the user token account, leave it unset to also unwrap SOL"
+                    .to_string(),
+            ));
+        }
+        if self.quote_response.swap_mode == SwapMode::ExactOut
+            && self.config.dynamic_slippage.is_some()
+        {
+            return Err(JupiterError::InvalidRequest(
+                "dynamic_slippage isn't supported with ExactOut".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl QuoteResponse {
+    /// Swap of this quote with the default [`TransactionConfig`], wrapping and unwrapping SOL through shared accounts
+    pub fn into_swap_request(self, user_public_key: Pubkey) -> SwapRequestBuilder {
+        SwapRequestBuilder {
+            request: SwapRequest {
+                user_public_key,
+                quote_response: self,
+                config: TransactionConfig::default(),
+            },
+            slippage: None,
+            platform_fee: None,
+        }
+    }
+}
+
+/// Fluent construction of a [`SwapRequest`], see [`QuoteResponse::into_swap_request`]
+#[derive(Debug, Clone)]
+pub struct SwapRequestBuilder {
+    request: SwapRequest,
+    slippage: Option<Slippage>,
+    platform_fee: Option<PlatformFeeConfig>,
+}
+
+impl SwapRequestBuilder {
+    /// Replaces the whole config, including the fields set so far, e.g. with [`TransactionConfig::low_latency`]
+    pub fn config(mut self, config: TransactionConfig) -> Self {
+        self.request.config = config;
+        self
+    }
+
+    pub fn wrap_and_unwrap_sol(mut self, wrap_and_unwrap_sol: bool) -> Self {
+        self.request.config.wrap_and_unwrap_sol = wrap_and_unwrap_sol;
+        self
+    }
+
+    pub fn use_shared_accounts(mut self, use_shared_accounts: bool) -> Self {
+        self.request.config.use_shared_accounts = use_shared_accounts;
+        self
+    }
+
+    pub fn dynamic_compute_unit_limit(mut self, dynamic_compute_unit_limit: bool) -> Self {
+        self.request.config.dynamic_compute_unit_limit = dynamic_compute_unit_limit;
+        self
+    }
+
+    pub fn as_legacy_transaction(mut self, as_legacy_transaction: bool) -> Self {
+        self.request.config.as_legacy_transaction = as_legacy_transaction;
+        self
+    }
+
+    /// Swap the amount received by prior instructions, see [`SwapInstructionsResponse::instructions_with_token_ledger`]
+    pub fn use_token_ledger(mut self, use_token_ledger: bool) -> Self {
+        self.request.config.use_token_ledger = use_token_ledger;
+        self
+    }
+
+    /// Has to be initialized, see [`SwapRequestBuilder::destination_wallet`] to send to the token account of another wallet
+    pub fn destination_token_account(mut self, destination_token_account: Pubkey) -> Self {
+        self.request.config.destination_token_account = Some(destination_token_account);
+        self
+    }
+
+    /// Send the output to the associated token account of `wallet`, owned by `token_program` of the output mint
+    pub fn destination_wallet(self, wallet: &Pubkey, token_program: &Pubkey) -> Self {
+        let destination_token_account = associated_token_address(
+            wallet,
+            &self.request.quote_response.output_mint,
+            token_program,
+        );
+        self.destination_token_account(destination_token_account)
+    }
+
+    /// Prefer [`SwapRequestBuilder::platform_fee`], which checks the fee of the quote
+    pub fn fee_account(mut self, fee_account: Pubkey) -> Self {
+        self.request.config.fee_account = Some(fee_account);
+        self
+    }
+
+    /// Clears `compute_unit_price_micro_lamports`, the two are mutually exclusive
+    pub fn prioritization_fee_lamports(
+        mut self,
+        prioritization_fee_lamports: PrioritizationFeeLamports,
+    ) -> Self {
+        self.request.config.prioritization_fee_lamports = Some(prioritization_fee_lamports);
+        self.request.config.compute_unit_price_micro_lamports = None;
+        self
+    }
+
+    /// Clears `prioritization_fee_lamports`, the two are mutually exclusive
+    pub fn compute_unit_price_micro_lamports(
+        mut self,
+        compute_unit_price_micro_lamports: ComputeUnitPriceMicroLamports,
+    ) -> Self {
+        self.request.config.compute_unit_price_micro_lamports =
+            Some(compute_unit_price_micro_lamports);
+        self.request.config.prioritization_fee_lamports = None;
+        self
+    }
+
+    /// Only dynamic slippage applies to the swap, fixed slippage is taken from the quote
+    pub fn slippage(mut self, slippage: impl Into<Slippage>) -> Self {
+        self.slippage = Some(slippage.into());
+        self
+    }
+
+    /// Fails on build when the quote wasn't requested with the same fee
+    pub fn platform_fee(mut self, platform_fee: PlatformFeeConfig) -> Self {
+        self.platform_fee = Some(platform_fee);
+        self
+    }
+
+    /// Fails with [`JupiterError::InvalidRequest`] as [`SwapRequest::validate`] does
+    pub fn build(mut self) -> Result<SwapRequest, JupiterError> {
+        if let Some(slippage) = self.slippage {
+            self.request.set_slippage(slippage)?;
+        }
+        if let Some(platform_fee) = self.platform_fee {
+            platform_fee.apply_to_swap(&mut self.request)?;
+        }
+        self.request.validate()?;
+        Ok(self.request)
+    }
+}
+
+/// How the priority fee was paid, see [`SwapResponse::fee_breakdown`]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub enum PrioritizationType {
@@ -33,31 +224,179 @@ pub enum PrioritizationType {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct DynamicSlippageReport {
+    /// Slippage the swap was built with
     pub slippage_bps: u16,
     pub other_amount: Option<u64>,
     /// Signed to convey positive and negative slippage
     pub simulated_incurred_slippage_bps: Option<i16>,
     pub amplification_ratio: Option<Decimal>,
+    /// Token category the slippage heuristic picked, e.g. `stable` or `lst`
+    pub category_name: Option<String>,
+    /// Upper bound of the slippage heuristic for the category
+    pub heuristic_max_slippage_bps: Option<u16>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct UiSimulationError {
-    error_code: String,
-    error: String,
+    pub error_code: String,
+    pub error: String,
+    /// Program logs of the failed simulation, when returned
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub logs: Vec<String>,
+}
+
+/// Custom error of the Jupiter program when the output is below the slippage threshold
+pub const SLIPPAGE_TOLERANCE_EXCEEDED_ERROR: u32 = 6001;
+
+impl UiSimulationError {
+    /// Code of a `custom program error: 0x1771` found in the error or the logs
+    pub fn custom_program_error(&self) -> Option<u32> {
+        std::iter::once(&self.error)
+            .chain(&self.logs)
+            .find_map(|line| {
+                let (_, code) = line.split_once("custom program error: 0x")?;
+                let code = code.split(|c: char| !c.is_ascii_hexdigit()).next()?;
+                u32::from_str_radix(code, 16).ok()
+            })
+    }
+
+    /// The price moved since quoting, re-quote rather than sending the transaction
+    pub fn is_slippage_tolerance_exceeded(&self) -> bool {
+        self.custom_program_error() == Some(SLIPPAGE_TOLERANCE_EXCEEDED_ERROR)
+    }
 }
 
+impl std::fmt::Display for UiSimulationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.error_code, self.error)
+    }
+}
+
+/// Blockhash the transaction was built with
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockhashWithMetadata {
+    /// Raw 32 bytes
+    pub blockhash: Vec<u8>,
+    pub last_valid_block_height: u64,
+    /// When the API fetched the blockhash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fetched_at: Option<FetchedAt>,
+}
+
+/// Serialized `SystemTime`
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FetchedAt {
+    pub secs_since_epoch: u64,
+    pub nanos_since_epoch: u32,
+}
+
+impl BlockhashWithMetadata {
+    pub fn to_hash(&self) -> Result<Hash, JupiterError> {
+        <[u8; HASH_BYTES]>::try_from(self.blockhash.as_slice())
+            .map(Hash::new_from_array)
+            .map_err(|_| {
+                JupiterError::MalformedResponse(format!(
+                    "blockhash has {} bytes instead of {HASH_BYTES}",
+                    self.blockhash.len()
+                ))
+            })
+    }
+
+    pub fn fetched_at(&self) -> Option<SystemTime> {
+        self.fetched_at.map(|fetched_at| {
+            UNIX_EPOCH + Duration::new(fetched_at.secs_since_epoch, fetched_at.nanos_since_epoch)
+        })
+    }
+
+    /// Time since the API fetched the blockhash, `None` when not reported
+    pub fn age(&self) -> Option<Duration> {
+        SystemTime::now().duration_since(self.fetched_at()?).ok()
+    }
+}
+
+/// Most compute units a transaction can request
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct SwapResponse {
     #[serde(with = "base64_serialize_deserialize")]
     pub swap_transaction: Vec<u8>,
+    /// The transaction can't land once the block height exceeds it, see [`SwapResponse::is_likely_expired`]
     pub last_valid_block_height: u64,
     pub prioritization_fee_lamports: u64,
+    /// Compute unit limit set by the compute budget instruction, fitted by simulation with `dynamic_compute_unit_limit`
     pub compute_unit_limit: u32,
     pub prioritization_type: Option<PrioritizationType>,
     pub dynamic_slippage_report: Option<DynamicSlippageReport>,
     pub simulation_error: Option<UiSimulationError>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blockhash_with_metadata: Option<BlockhashWithMetadata>,
+}
+
+impl SwapResponse {
+    /// Blocks left after the current one before the transaction expires, `None` once it did
+    ///
+    /// `Some(0)` at the last valid block height, where the transaction can still land.
+    pub fn blocks_remaining(&self, current_block_height: u64) -> Option<u64> {
+        self.last_valid_block_height
+            .checked_sub(current_block_height)
+    }
+
+    /// Sending is pointless, the transaction would expire before it lands
+    ///
+    /// `current_block_height` is from `getBlockHeight`, not the slot.
+    pub fn is_likely_expired(&self, current_block_height: u64) -> bool {
+        self.blocks_remaining(current_block_height).is_none()
+    }
+
+    /// Compute unit limit fitted by simulation, `None` when the transaction requests the maximum
+    ///
+    /// The maximum is requested without `dynamic_compute_unit_limit` or when the simulation failed.
+    pub fn fitted_compute_unit_limit(&self) -> Option<u32> {
+        (self.compute_unit_limit < MAX_COMPUTE_UNIT_LIMIT).then_some(self.compute_unit_limit)
+    }
+
+    /// Decodes `swap_transaction`, with a legacy message when requested with `as_legacy_transaction`
+    pub fn versioned_transaction(&self) -> Result<VersionedTransaction, JupiterError> {
+        bincode::deserialize(&self.swap_transaction)
+            .map_err(|e| JupiterError::MalformedResponse(format!("invalid swap transaction: {e}")))
+    }
+
+    /// Decodes `swap_transaction`, legacy when requested with `as_legacy_transaction`
+    pub fn transaction(&self) -> Result<SwapTransaction, JupiterError> {
+        let transaction = self.versioned_transaction()?;
+        let signatures = transaction.signatures;
+        Ok(match transaction.message {
+            VersionedMessage::Legacy(message) => SwapTransaction::Legacy(Transaction {
+                signatures,
+                message,
+            }),
+            message => SwapTransaction::Versioned(VersionedTransaction {
+                signatures,
+                message,
+            }),
+        })
+    }
+
+    /// Fails with [`JupiterError::MalformedResponse`] unless requested with `as_legacy_transaction`
+    pub fn legacy_transaction(&self) -> Result<Transaction, JupiterError> {
+        match self.transaction()? {
+            SwapTransaction::Legacy(transaction) => Ok(transaction),
+            SwapTransaction::Versioned(_) => Err(JupiterError::MalformedResponse(
+                "swap transaction is versioned, request it with as_legacy_transaction".to_string(),
+            )),
+        }
+    }
+}
+
+/// Decoded [`SwapResponse::swap_transaction`], for wallets and programs not supporting versioned transactions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SwapTransaction {
+    Legacy(Transaction),
+    Versioned(VersionedTransaction),
 }
 
 pub mod base64_serialize_deserialize {
@@ -81,6 +420,57 @@ pub mod base64_serialize_deserialize {
     }
 }
 
+/// Same as [`base64_serialize_deserialize`] for an optional field
+pub mod option_base64_serialize_deserialize {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(v: &Option<Vec<u8>>, s: S) -> Result<S::Ok, S::Error> {
+        v.as_ref().map(|v| STANDARD.encode(v)).serialize(s)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Vec<u8>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<String>::deserialize(deserializer)?
+            .filter(|field_string| !field_string.is_empty())
+            .map(|field_string| {
+                STANDARD
+                    .decode(field_string)
+                    .map_err(|e| de::Error::custom(format!("base64 decoding error: {:?}", e)))
+            })
+            .transpose()
+    }
+}
+
+/// Same as [`base64_serialize_deserialize`] for a list
+pub mod base64_vec_serialize_deserialize {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(v: &[Vec<u8>], s: S) -> Result<S::Ok, S::Error> {
+        v.iter()
+            .map(|v| STANDARD.encode(v))
+            .collect::<Vec<_>>()
+            .serialize(s)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Vec<Vec<u8>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Vec::<String>::deserialize(deserializer)?
+            .into_iter()
+            .map(|field_string| {
+                STANDARD
+                    .decode(field_string)
+                    .map_err(|e| de::Error::custom(format!("base64 decoding error: {:?}", e)))
+            })
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SwapInstructionsResponse {
     pub token_ledger_instruction: Option<Instruction>,
@@ -92,12 +482,61 @@ pub struct SwapInstructionsResponse {
     /// Other instructions that should be included in the transaction.
     /// Now, it should only have the Jito tip instruction.
     pub other_instructions: Vec<Instruction>,
+    /// User instructions before the swap, see [`SwapInstructionsResponse::with_pre_instructions`]
+    pub pre_instructions: Vec<Instruction>,
+    /// User instructions after the swap, see [`SwapInstructionsResponse::with_post_instructions`]
+    pub post_instructions: Vec<Instruction>,
     pub address_lookup_table_addresses: Vec<Pubkey>,
     pub prioritization_fee_lamports: u64,
+    /// Compute unit limit set by the compute budget instruction, fitted by simulation with `dynamic_compute_unit_limit`
     pub compute_unit_limit: u32,
     pub prioritization_type: Option<PrioritizationType>,
     pub dynamic_slippage_report: Option<DynamicSlippageReport>,
     pub simulation_error: Option<UiSimulationError>,
+    pub blockhash_with_metadata: Option<BlockhashWithMetadata>,
+}
+
+impl SwapInstructionsResponse {
+    /// Swap whatever `instructions` transfer into the input token account, requested with `use_token_ledger`
+    ///
+    /// The token ledger instruction records the balance before `instructions`,
+    /// the swap then spends the difference. Returns all instructions in transaction order.
+    pub fn instructions_with_token_ledger(
+        &self,
+        instructions: impl IntoIterator<Item = Instruction>,
+    ) -> Result<Vec<Instruction>, JupiterError> {
+        if self.token_ledger_instruction.is_none() {
+            return Err(JupiterError::InvalidRequest(
+                "no token ledger instruction, swap with use_token_ledger".to_string(),
+            ));
+        }
+        let mut response = self.clone();
+        response.pre_instructions = instructions
+            .into_iter()
+            .chain(response.pre_instructions)
+            .collect();
+        Ok(response.instructions().cloned().collect())
+    }
+
+    /// Runs `instructions` after the setup and token ledger instructions, right before the swap
+    ///
+    /// Token accounts created by the setup instructions exist, e.g. to fund the input token account.
+    pub fn with_pre_instructions(
+        mut self,
+        instructions: impl IntoIterator<Item = Instruction>,
+    ) -> Self {
+        self.pre_instructions.extend(instructions);
+        self
+    }
+
+    /// Runs `instructions` after the cleanup instruction, once wrapped SOL is unwrapped, and before the Jito tip
+    pub fn with_post_instructions(
+        mut self,
+        instructions: impl IntoIterator<Item = Instruction>,
+    ) -> Self {
+        self.post_instructions.extend(instructions);
+        self
+    }
 }
 
 // Duplicate for deserialization
@@ -119,82 +558,128 @@ pub struct SwapInstructionsResponseInternal {
     prioritization_type: Option<PrioritizationType>,
     dynamic_slippage_report: Option<DynamicSlippageReport>,
     simulation_error: Option<UiSimulationError>,
+    #[serde(default)]
+    blockhash_with_metadata: Option<BlockhashWithMetadata>,
 }
 
+/// Kept as raw strings so that malformed values surface as [`JupiterError::MalformedResponse`]
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 struct InstructionInternal {
-    #[serde(with = "field_as_string")]
-    pub program_id: Pubkey,
+    pub program_id: String,
     pub accounts: Vec<AccountMetaInternal>,
-    #[serde(with = "base64_serialize_deserialize")]
-    pub data: Vec<u8>,
+    /// Base64 encoded
+    pub data: String,
 }
 
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct AccountMetaInternal {
-    #[serde(with = "field_as_string")]
-    pub pubkey: Pubkey,
+    pub pubkey: String,
     pub is_signer: bool,
     pub is_writable: bool,
 }
 
-impl From<AccountMetaInternal> for AccountMeta {
-    fn from(val: AccountMetaInternal) -> Self {
-        AccountMeta {
-            pubkey: val.pubkey,
+pub(crate) fn parse_pubkey(pubkey: &str, field: &str) -> Result<Pubkey, JupiterError> {
+    Pubkey::from_str(pubkey)
+        .map_err(|e| JupiterError::MalformedResponse(format!("invalid {field} {pubkey}: {e}")))
+}
+
+impl TryFrom<AccountMetaInternal> for AccountMeta {
+    type Error = JupiterError;
+
+    fn try_from(val: AccountMetaInternal) -> Result<Self, Self::Error> {
+        Ok(AccountMeta {
+            pubkey: parse_pubkey(&val.pubkey, "account pubkey")?,
             is_signer: val.is_signer,
             is_writable: val.is_writable,
-        }
+        })
     }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
-struct PubkeyInternal(#[serde(with = "field_as_string")] Pubkey);
-
-impl From<InstructionInternal> for Instruction {
-    fn from(val: InstructionInternal) -> Self {
-        Instruction {
-            program_id: val.program_id,
-            accounts: val.accounts.into_iter().map(Into::into).collect(),
-            data: val.data,
-        }
+struct PubkeyInternal(String);
+
+impl TryFrom<InstructionInternal> for Instruction {
+    type Error = JupiterError;
+
+    fn try_from(val: InstructionInternal) -> Result<Self, Self::Error> {
+        Ok(Instruction {
+            program_id: parse_pubkey(&val.program_id, "program id")?,
+            accounts: try_into_vec(val.accounts)?,
+            data: STANDARD.decode(&val.data).map_err(|e| {
+                JupiterError::MalformedResponse(format!("invalid base64 instruction data: {e}"))
+            })?,
+        })
     }
 }
 
-impl From<SwapInstructionsResponseInternal> for SwapInstructionsResponse {
-    fn from(value: SwapInstructionsResponseInternal) -> Self {
-        Self {
-            token_ledger_instruction: value.token_ledger_instruction.map(Into::into),
-            compute_budget_instructions: value
-                .compute_budget_instructions
-                .into_iter()
-                .map(Into::into)
-                .collect(),
-            setup_instructions: value
-                .setup_instructions
-                .into_iter()
-                .map(Into::into)
-                .collect(),
-            swap_instruction: value.swap_instruction.into(),
-            cleanup_instruction: value.cleanup_instruction.map(Into::into),
-            other_instructions: value
-                .other_instructions
-                .into_iter()
-                .map(Into::into)
-                .collect(),
+fn try_into_vec<T: TryInto<U, Error = JupiterError>, U>(
+    values: Vec<T>,
+) -> Result<Vec<U>, JupiterError> {
+    values.into_iter().map(TryInto::try_into).collect()
+}
+
+impl TryFrom<SwapInstructionsResponseInternal> for SwapInstructionsResponse {
+    type Error = JupiterError;
+
+    fn try_from(value: SwapInstructionsResponseInternal) -> Result<Self, Self::Error> {
+        Ok(Self {
+            token_ledger_instruction: value
+                .token_ledger_instruction
+                .map(TryInto::try_into)
+                .transpose()?,
+            compute_budget_instructions: try_into_vec(value.compute_budget_instructions)?,
+            setup_instructions: try_into_vec(value.setup_instructions)?,
+            swap_instruction: value.swap_instruction.try_into()?,
+            cleanup_instruction: value
+                .cleanup_instruction
+                .map(TryInto::try_into)
+                .transpose()?,
+            other_instructions: try_into_vec(value.other_instructions)?,
+            pre_instructions: Vec::new(),
+            post_instructions: Vec::new(),
             address_lookup_table_addresses: value
                 .address_lookup_table_addresses
-                .into_iter()
-                .map(|p| p.0)
-                .collect(),
+                .iter()
+                .map(|p| parse_pubkey(&p.0, "address lookup table address"))
+                .collect::<Result<_, _>>()?,
             prioritization_fee_lamports: value.prioritization_fee_lamports,
             compute_unit_limit: value.compute_unit_limit,
             prioritization_type: value.prioritization_type,
             dynamic_slippage_report: value.dynamic_slippage_report,
             simulation_error: value.simulation_error,
+            blockhash_with_metadata: value.blockhash_with_metadata,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn swap_response(last_valid_block_height: u64) -> SwapResponse {
+        SwapResponse {
+            swap_transaction: Vec::new(),
+            last_valid_block_height,
+            prioritization_fee_lamports: 0,
+            compute_unit_limit: MAX_COMPUTE_UNIT_LIMIT,
+            prioritization_type: None,
+            dynamic_slippage_report: None,
+            simulation_error: None,
+            blockhash_with_metadata: None,
         }
     }
+
+    #[test]
+    fn expires_after_the_last_valid_block_height() {
+        let swap_response = swap_response(100);
+        assert_eq!(swap_response.blocks_remaining(99), Some(1));
+        assert!(!swap_response.is_likely_expired(99));
+        assert_eq!(swap_response.blocks_remaining(100), Some(0));
+        assert!(!swap_response.is_likely_expired(100));
+        assert_eq!(swap_response.blocks_remaining(101), None);
+        assert!(swap_response.is_likely_expired(101));
+    }
 }
@@ -0,0 +1,26 @@
+//! Token programs and associated token account derivation
+
+use solana_sdk::{pubkey, pubkey::Pubkey};
+
+pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// Associated token account of `wallet` for `mint`, owned by `token_program`
+pub fn associated_token_address(wallet: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[wallet.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Whether `token_account` is an associated token account of `wallet` for `mint`, under either token program
+pub fn is_associated_token_address(token_account: &Pubkey, wallet: &Pubkey, mint: &Pubkey) -> bool {
+    [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID]
+        .iter()
+        .any(|token_program| {
+            associated_token_address(wallet, mint, token_program) == *token_account
+        })
+}
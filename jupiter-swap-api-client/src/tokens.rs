@@ -0,0 +1,211 @@
+//! Token API v1
+
+use std::{collections::HashMap, fmt};
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    quote::QuoteResponse,
+    request_options::{Endpoint, RequestOptions},
+    serde_helpers::{field_as_string, option_field_as_string},
+    swap::parse_pubkey,
+    JupiterError, JupiterSwapApiClient,
+};
+
+/// Tag of the token list
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TokenTag {
+    Verified,
+    Unknown,
+    Community,
+    Strict,
+    Lst,
+    BirdeyeTrending,
+    Pump,
+    Moonshot,
+    Token2022,
+    /// Any tag this client doesn't know about yet
+    Other(String),
+}
+
+impl TokenTag {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Verified => "verified",
+            Self::Unknown => "unknown",
+            Self::Community => "community",
+            Self::Strict => "strict",
+            Self::Lst => "lst",
+            Self::BirdeyeTrending => "birdeye-trending",
+            Self::Pump => "pump",
+            Self::Moonshot => "moonshot",
+            Self::Token2022 => "token-2022",
+            Self::Other(tag) => tag,
+        }
+    }
+}
+
+impl From<&str> for TokenTag {
+    fn from(tag: &str) -> Self {
+        match tag {
+            "verified" => Self::Verified,
+            "unknown" => Self::Unknown,
+            "community" => Self::Community,
+            "strict" => Self::Strict,
+            "lst" => Self::Lst,
+            "birdeye-trending" => Self::BirdeyeTrending,
+            "pump" => Self::Pump,
+            "moonshot" => Self::Moonshot,
+            "token-2022" => Self::Token2022,
+            _ => Self::Other(tag.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for TokenTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for TokenTag {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for TokenTag {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self::from(String::deserialize(deserializer)?.as_str()))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TokenInfo {
+    #[serde(with = "field_as_string")]
+    pub address: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    #[serde(rename = "logoURI")]
+    pub logo_uri: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<TokenTag>,
+    /// USD volume of the last 24 hours
+    #[serde(default)]
+    pub daily_volume: Option<f64>,
+    /// RFC 3339 timestamp of when the token got indexed
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default, with = "option_field_as_string")]
+    pub freeze_authority: Option<Pubkey>,
+    #[serde(default, with = "option_field_as_string")]
+    pub mint_authority: Option<Pubkey>,
+    #[serde(default, with = "option_field_as_string")]
+    pub permanent_delegate: Option<Pubkey>,
+    #[serde(default)]
+    pub minted_at: Option<String>,
+    /// e.g. `coingeckoId`
+    #[serde(default)]
+    pub extensions: HashMap<String, serde_json::Value>,
+}
+
+impl TokenInfo {
+    pub fn is_verified(&self) -> bool {
+        self.tags.contains(&TokenTag::Verified)
+    }
+}
+
+impl JupiterSwapApiClient {
+    /// Every indexed token
+    ///
+    /// The list is large, read with a body limit of at least
+    /// [`TOKENS_MAX_RESPONSE_BODY_SIZE`](crate::request_options::TOKENS_MAX_RESPONSE_BODY_SIZE).
+    /// Prefer [`JupiterSwapApiClient::tokens_by_tag`] when possible.
+    pub async fn tokens(&self) -> Result<Vec<TokenInfo>, JupiterError> {
+        self.send(
+            Endpoint::Tokens,
+            |api_base_path| self.client.get(format!("{api_base_path}/tokens/v1/all")),
+            &RequestOptions::default(),
+        )
+        .await
+        .map(|(tokens, _)| tokens)
+    }
+
+    /// Tokens having any of `tags`
+    pub async fn tokens_by_tag(&self, tags: &[TokenTag]) -> Result<Vec<TokenInfo>, JupiterError> {
+        if tags.is_empty() {
+            return Err(JupiterError::InvalidRequest(
+                "tags can't be empty".to_string(),
+            ));
+        }
+        let tags = tags
+            .iter()
+            .map(TokenTag::as_str)
+            .collect::<Vec<_>>()
+            .join(",");
+        self.send(
+            Endpoint::Tokens,
+            |api_base_path| {
+                self.client
+                    .get(format!("{api_base_path}/tokens/v1/tagged/{tags}"))
+            },
+            &RequestOptions::default(),
+        )
+        .await
+        .map(|(tokens, _)| tokens)
+    }
+
+    /// Mints that can be swapped, see [`JupiterSwapApiClient::indexed_route_map`] for the pairs
+    pub async fn tradable_mints(&self) -> Result<Vec<Pubkey>, JupiterError> {
+        let (mints, _) = self
+            .send::<Vec<String>>(
+                Endpoint::Tokens,
+                |api_base_path| {
+                    self.client
+                        .get(format!("{api_base_path}/tokens/v1/mints/tradable"))
+                },
+                &RequestOptions::default(),
+            )
+            .await?;
+        mints
+            .iter()
+            .map(|mint| parse_pubkey(mint, "tradable mint"))
+            .collect()
+    }
+
+    /// Metadata of `mint`, `None` if it isn't indexed
+    pub async fn token(&self, mint: &Pubkey) -> Result<Option<TokenInfo>, JupiterError> {
+        let response = self
+            .send::<Option<TokenInfo>>(
+                Endpoint::Tokens,
+                |api_base_path| {
+                    self.client
+                        .get(format!("{api_base_path}/tokens/v1/token/{mint}"))
+                },
+                &RequestOptions::default(),
+            )
+            .await;
+        match response {
+            Ok((token, _)) => Ok(token),
+            Err(e) if e.status_code() == Some(reqwest::StatusCode::NOT_FOUND) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// [`QuoteResponse::effective_price`] with the decimals of the token API, `None` for unknown mints
+    pub async fn effective_price(
+        &self,
+        quote_response: &QuoteResponse,
+    ) -> Result<Option<Decimal>, JupiterError> {
+        let Some(input_token) = self.token(&quote_response.input_mint).await? else {
+            return Ok(None);
+        };
+        let Some(output_token) = self.token(&quote_response.output_mint).await? else {
+            return Ok(None);
+        };
+        Ok(quote_response.effective_price(input_token.decimals, output_token.decimals))
+    }
+}
@@ -0,0 +1,99 @@
+//! Compiling swap instructions into a transaction ready to sign
+
+use solana_sdk::{
+    address_lookup_table::AddressLookupTableAccount,
+    hash::Hash,
+    message::{v0, VersionedMessage},
+    packet::PACKET_DATA_SIZE,
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::VersionedTransaction,
+};
+
+use crate::{swap::SwapInstructionsResponse, JupiterError};
+
+impl SwapInstructionsResponse {
+    /// V0 message of [`SwapInstructionsResponse::instructions`]
+    ///
+    /// `address_lookup_table_accounts` are the resolved [`SwapInstructionsResponse::address_lookup_table_addresses`].
+    pub fn compile_message(
+        &self,
+        payer: &Pubkey,
+        address_lookup_table_accounts: &[AddressLookupTableAccount],
+        recent_blockhash: Hash,
+    ) -> Result<VersionedMessage, JupiterError> {
+        let instructions = self.instructions().cloned().collect::<Vec<_>>();
+        let message = v0::Message::try_compile(
+            payer,
+            &instructions,
+            address_lookup_table_accounts,
+            recent_blockhash,
+        )
+        .map_err(|e| JupiterError::Transaction(format!("failed to compile message: {e}")))?;
+        Ok(VersionedMessage::V0(message))
+    }
+
+    /// Unsigned transaction, with a default signature per required signer to replace when signing
+    pub fn compile_transaction(
+        &self,
+        payer: &Pubkey,
+        address_lookup_table_accounts: &[AddressLookupTableAccount],
+        recent_blockhash: Hash,
+    ) -> Result<VersionedTransaction, JupiterError> {
+        let message =
+            self.compile_message(payer, address_lookup_table_accounts, recent_blockhash)?;
+        Ok(VersionedTransaction {
+            signatures: vec![
+                Signature::default();
+                usize::from(message.header().num_required_signatures)
+            ],
+            message,
+        })
+    }
+}
+
+/// Size of `transaction` once serialized for the wire
+pub fn serialized_size(transaction: &VersionedTransaction) -> Result<usize, JupiterError> {
+    bincode::serialized_size(transaction)
+        .map(|size| size as usize)
+        .map_err(|e| JupiterError::Transaction(format!("failed to serialize transaction: {e}")))
+}
+
+/// Fails with [`JupiterError::TransactionTooLarge`] when `transaction` exceeds [`PACKET_DATA_SIZE`]
+pub fn check_serialized_size(transaction: &VersionedTransaction) -> Result<usize, JupiterError> {
+    let size = serialized_size(transaction)?;
+    if size > PACKET_DATA_SIZE {
+        return Err(JupiterError::TransactionTooLarge {
+            size,
+            limit: PACKET_DATA_SIZE,
+        });
+    }
+    Ok(size)
+}
+
+impl SwapInstructionsResponse {
+    /// Size of the compiled transaction, signatures included, the blockhash not affecting it
+    pub fn estimated_serialized_size(
+        &self,
+        payer: &Pubkey,
+        address_lookup_table_accounts: &[AddressLookupTableAccount],
+    ) -> Result<usize, JupiterError> {
+        let transaction =
+            self.compile_transaction(payer, address_lookup_table_accounts, Hash::default())?;
+        serialized_size(&transaction)
+    }
+
+    /// Like [`SwapInstructionsResponse::compile_transaction`], failing with [`JupiterError::TransactionTooLarge`]
+    /// instead of a rejection by the RPC node
+    pub fn compile_transaction_checked(
+        &self,
+        payer: &Pubkey,
+        address_lookup_table_accounts: &[AddressLookupTableAccount],
+        recent_blockhash: Hash,
+    ) -> Result<VersionedTransaction, JupiterError> {
+        let transaction =
+            self.compile_transaction(payer, address_lookup_table_accounts, recent_blockhash)?;
+        check_serialized_size(&transaction)?;
+        Ok(transaction)
+    }
+}
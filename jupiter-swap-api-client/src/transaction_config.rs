@@ -22,11 +22,17 @@ pub enum PriorityLevel {
     VeryHigh,
 }
 
+/// `prioritizationFeeLamports`, serialized in the nested shape the API expects
 #[derive(Deserialize, Debug, PartialEq, Copy, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub enum PrioritizationFeeLamports {
+    /// `{"autoMultiplier": 2}`, the automatic fee multiplied
     AutoMultiplier(u32),
+    /// `{"jitoTipLamports": 1000}`, a Jito tip instead of a priority fee
     JitoTipLamports(u64),
+    /// `{"priorityLevelWithMaxLamports": {...}}`, the fee of a priority level estimated from recent fees, capped
+    ///
+    /// `global` estimates from the fees of the whole network rather than of the accounts written to.
     #[serde(rename_all = "camelCase")]
     PriorityLevelWithMaxLamports {
         priority_level: PriorityLevel,
@@ -34,15 +40,34 @@ pub enum PrioritizationFeeLamports {
         #[serde(default)]
         global: bool,
     },
+    /// `"auto"`, the fee picked by the API
     #[default]
     #[serde(untagged, deserialize_with = "auto")]
     Auto,
+    /// An exact fee in lamports
     #[serde(untagged)]
     Lamports(u64),
+    /// `"disabled"`, no priority fee
     #[serde(untagged, deserialize_with = "disabled")]
     Disabled,
 }
 
+impl PrioritizationFeeLamports {
+    /// Same as [`PrioritizationFeeLamports::Lamports`]
+    pub fn exact(lamports: u64) -> Self {
+        Self::Lamports(lamports)
+    }
+
+    /// Local fee market estimate of `priority_level`, capped to `max_lamports`
+    pub fn priority_level(priority_level: PriorityLevel, max_lamports: u64) -> Self {
+        Self::PriorityLevelWithMaxLamports {
+            priority_level,
+            max_lamports,
+            global: false,
+        }
+    }
+}
+
 impl Serialize for PrioritizationFeeLamports {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -135,11 +160,22 @@ pub struct DynamicSlippageSettings {
     pub max_bps: Option<u16>,
 }
 
+impl DynamicSlippageSettings {
+    pub fn new(min_bps: u16, max_bps: u16) -> Self {
+        Self {
+            min_bps: Some(min_bps),
+            max_bps: Some(max_bps),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 #[serde(default)]
 pub struct TransactionConfig {
     /// Wrap and unwrap SOL. Will be ignored if `destination_token_account` is set because the `destination_token_account` may belong to a different user that we have no authority to close.
+    ///
+    /// Default: true
     pub wrap_and_unwrap_sol: bool,
     /// Allow optimized WSOL token account by using transfer, assign with seed, allocate with seed then initialize account 3 instead of the expensive associated token account process
     pub allow_optimized_wrapped_sol_token_account: bool,
@@ -176,6 +212,8 @@ pub struct TransactionConfig {
     pub use_token_ledger: bool,
     /// Skip RPC calls and assume the user account do not exist,
     /// as a result all setup instruction will be populated but no RPC call will be done for user related accounts (token accounts, openbook open orders...)
+    ///
+    /// Default: false
     pub skip_user_accounts_rpc_calls: bool,
     /// Providing keyed ui accounts allow loading AMMs that are not in the market cache
     /// If a keyed ui account is the AMM state, it has to be provided with its params according to the market cache format
@@ -212,6 +250,43 @@ impl Default for TransactionConfig {
     }
 }
 
+impl TransactionConfig {
+    /// Land fast: compute unit limit fitted by simulation and an automatic priority fee
+    pub fn low_latency() -> Self {
+        Self {
+            dynamic_compute_unit_limit: true,
+            prioritization_fee_lamports: Some(PrioritizationFeeLamports::Auto),
+            ..Self::default()
+        }
+    }
+
+    /// Keep fees low: fitted compute unit limit and a medium priority fee capped to 0.001 SOL
+    pub fn conservative() -> Self {
+        Self {
+            dynamic_compute_unit_limit: true,
+            prioritization_fee_lamports: Some(
+                PrioritizationFeeLamports::PriorityLevelWithMaxLamports {
+                    priority_level: PriorityLevel::Medium,
+                    max_lamports: 1_000_000,
+                    global: false,
+                },
+            ),
+            ..Self::default()
+        }
+    }
+
+    /// Send through Jito with a tip of `tip_lamports` instead of a priority fee
+    pub fn jito(tip_lamports: u64) -> Self {
+        Self {
+            dynamic_compute_unit_limit: true,
+            prioritization_fee_lamports: Some(PrioritizationFeeLamports::JitoTipLamports(
+                tip_lamports,
+            )),
+            ..Self::default()
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct KeyedUiAccount {
     pub pubkey: String,
@@ -220,3 +295,35 @@ pub struct KeyedUiAccount {
     /// Additional data an Amm requires, Amm dependent and decoded in the Amm implementation
     pub params: Option<Value>,
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn default_serializes_toggles() {
+        let value = serde_json::to_value(TransactionConfig::default()).unwrap();
+        assert_eq!(value["wrapAndUnwrapSol"], json!(true));
+        assert_eq!(value["useSharedAccounts"], json!(true));
+        assert_eq!(value["skipUserAccountsRpcCalls"], json!(false));
+    }
+
+    #[test]
+    fn toggles_round_trip() {
+        let config: TransactionConfig = serde_json::from_value(json!({
+            "wrapAndUnwrapSol": false,
+            "useSharedAccounts": false,
+            "skipUserAccountsRpcCalls": true,
+        }))
+        .unwrap();
+        assert!(!config.wrap_and_unwrap_sol);
+        assert!(!config.use_shared_accounts);
+        assert!(config.skip_user_accounts_rpc_calls);
+
+        let round_tripped: TransactionConfig =
+            serde_json::from_value(serde_json::to_value(&config).unwrap()).unwrap();
+        assert_eq!(round_tripped, config);
+    }
+}
@@ -0,0 +1,304 @@
+//! Trigger API v1, limit orders
+
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    request_options::{Endpoint, RequestOptions},
+    serde_helpers::{field_as_string, option_field_as_string},
+    swap::{base64_serialize_deserialize, base64_vec_serialize_deserialize},
+    JupiterError, JupiterSwapApiClient,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateOrderParams {
+    /// Amount of the input mint to sell
+    #[serde(with = "field_as_string")]
+    pub making_amount: u64,
+    /// Amount of the output mint to receive, setting the limit price
+    #[serde(with = "field_as_string")]
+    pub taking_amount: u64,
+    /// Unix timestamp in seconds, the order never expires when not set
+    #[serde(
+        default,
+        with = "option_field_as_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub expired_at: Option<i64>,
+    /// Slippage makes the order execute like a market order once triggered
+    #[serde(
+        default,
+        with = "option_field_as_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub slippage_bps: Option<u16>,
+    /// Integrator fee, collected into `fee_account`
+    #[serde(
+        default,
+        with = "option_field_as_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub fee_bps: Option<u16>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateOrderRequest {
+    #[serde(with = "field_as_string")]
+    pub input_mint: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub output_mint: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub maker: Pubkey,
+    /// Pays for the order account rent, usually the maker
+    #[serde(with = "field_as_string")]
+    pub payer: Pubkey,
+    pub params: CreateOrderParams,
+    /// Micro lamports, or `auto`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compute_unit_price: Option<String>,
+    #[serde(
+        default,
+        with = "option_field_as_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub fee_account: Option<Pubkey>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wrap_and_unwrap_sol: Option<bool>,
+}
+
+impl CreateOrderRequest {
+    /// Order paid for by the maker
+    pub fn new(
+        maker: Pubkey,
+        input_mint: Pubkey,
+        output_mint: Pubkey,
+        making_amount: u64,
+        taking_amount: u64,
+    ) -> Self {
+        Self {
+            input_mint,
+            output_mint,
+            maker,
+            payer: maker,
+            params: CreateOrderParams {
+                making_amount,
+                taking_amount,
+                expired_at: None,
+                slippage_bps: None,
+                fee_bps: None,
+            },
+            compute_unit_price: None,
+            fee_account: None,
+            wrap_and_unwrap_sol: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateOrderResponse {
+    pub request_id: String,
+    /// Unsigned versioned transaction creating the order
+    #[serde(with = "base64_serialize_deserialize")]
+    pub transaction: Vec<u8>,
+    /// Order account
+    #[serde(with = "field_as_string")]
+    pub order: Pubkey,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelOrderRequest {
+    #[serde(with = "field_as_string")]
+    pub maker: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub order: Pubkey,
+    /// Micro lamports, or `auto`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compute_unit_price: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelOrdersRequest {
+    #[serde(with = "field_as_string")]
+    pub maker: Pubkey,
+    /// Every open order of the maker when empty
+    #[serde(
+        serialize_with = "serialize_pubkeys",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub orders: Vec<Pubkey>,
+    /// Micro lamports, or `auto`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compute_unit_price: Option<String>,
+}
+
+fn serialize_pubkeys<S: serde::Serializer>(pubkeys: &[Pubkey], s: S) -> Result<S::Ok, S::Error> {
+    s.collect_seq(pubkeys.iter().map(ToString::to_string))
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelOrderResponse {
+    pub request_id: String,
+    /// Unsigned versioned transaction cancelling the order
+    #[serde(with = "base64_serialize_deserialize")]
+    pub transaction: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelOrdersResponse {
+    pub request_id: String,
+    /// Unsigned versioned transactions, the API batches several orders per transaction
+    #[serde(with = "base64_vec_serialize_deserialize")]
+    pub transactions: Vec<Vec<u8>>,
+}
+
+/// Which orders [`JupiterSwapApiClient::get_trigger_orders`] lists
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TriggerOrderStatus {
+    Active,
+    /// Filled, cancelled and expired orders
+    History,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerOrder {
+    #[serde(with = "field_as_string")]
+    pub user_pubkey: Pubkey,
+    /// Order account
+    #[serde(with = "field_as_string")]
+    pub order_key: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub input_mint: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub output_mint: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub raw_making_amount: u64,
+    #[serde(with = "field_as_string")]
+    pub raw_taking_amount: u64,
+    /// Left to fill, in atomic units
+    #[serde(with = "field_as_string")]
+    pub raw_remaining_making_amount: u64,
+    #[serde(with = "field_as_string")]
+    pub raw_remaining_taking_amount: u64,
+    #[serde(default, with = "option_field_as_string")]
+    pub slippage_bps: Option<u16>,
+    /// RFC 3339 timestamps
+    pub created_at: String,
+    pub updated_at: String,
+    pub expired_at: Option<String>,
+    /// e.g. `Open`, `Completed` or `Cancelled`
+    pub status: String,
+    pub open_tx: Option<String>,
+    pub close_tx: Option<String>,
+}
+
+impl TriggerOrder {
+    pub fn raw_filled_making_amount(&self) -> u64 {
+        self.raw_making_amount
+            .saturating_sub(self.raw_remaining_making_amount)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerOrdersResponse {
+    pub orders: Vec<TriggerOrder>,
+    /// 1 based
+    pub page: u32,
+    pub total_pages: u32,
+}
+
+impl JupiterSwapApiClient {
+    /// Build the transaction creating a limit order, to be signed by the maker and payer
+    pub async fn create_order(
+        &self,
+        create_order_request: &CreateOrderRequest,
+    ) -> Result<CreateOrderResponse, JupiterError> {
+        let params = &create_order_request.params;
+        if params.making_amount == 0 || params.taking_amount == 0 {
+            return Err(JupiterError::InvalidRequest(
+                "making_amount and taking_amount have to be greater than 0".to_string(),
+            ));
+        }
+        self.send(
+            Endpoint::TriggerCreateOrder,
+            |api_base_path| {
+                self.client
+                    .post(format!("{api_base_path}/trigger/v1/createOrder"))
+                    .json(create_order_request)
+            },
+            &RequestOptions::default(),
+        )
+        .await
+        .map(|(create_order_response, _)| create_order_response)
+    }
+
+    /// Build the transaction cancelling a single order
+    pub async fn cancel_order(
+        &self,
+        cancel_order_request: &CancelOrderRequest,
+    ) -> Result<CancelOrderResponse, JupiterError> {
+        self.send(
+            Endpoint::TriggerCancelOrder,
+            |api_base_path| {
+                self.client
+                    .post(format!("{api_base_path}/trigger/v1/cancelOrder"))
+                    .json(cancel_order_request)
+            },
+            &RequestOptions::default(),
+        )
+        .await
+        .map(|(cancel_order_response, _)| cancel_order_response)
+    }
+
+    /// Build the transactions cancelling several orders, all open orders of the maker by default
+    ///
+    /// Each transaction has to be signed and sent, they are independent of each other.
+    pub async fn cancel_orders(
+        &self,
+        cancel_orders_request: &CancelOrdersRequest,
+    ) -> Result<CancelOrdersResponse, JupiterError> {
+        self.send(
+            Endpoint::TriggerCancelOrder,
+            |api_base_path| {
+                self.client
+                    .post(format!("{api_base_path}/trigger/v1/cancelOrders"))
+                    .json(cancel_orders_request)
+            },
+            &RequestOptions::default(),
+        )
+        .await
+        .map(|(cancel_orders_response, _)| cancel_orders_response)
+    }
+
+    /// Orders of `wallet`, `page` starts at 1
+    pub async fn get_trigger_orders(
+        &self,
+        wallet: &Pubkey,
+        status: TriggerOrderStatus,
+        page: u32,
+    ) -> Result<TriggerOrdersResponse, JupiterError> {
+        let user = wallet.to_string();
+        self.send(
+            Endpoint::TriggerOrders,
+            |api_base_path| {
+                self.client
+                    .get(format!("{api_base_path}/trigger/v1/getTriggerOrders"))
+                    .query(&[("user", &user)])
+                    .query(&[("orderStatus", status)])
+                    .query(&[("page", page)])
+            },
+            &RequestOptions::default(),
+        )
+        .await
+        .map(|(trigger_orders_response, _)| trigger_orders_response)
+    }
+}
@@ -0,0 +1,384 @@
+//! Ultra API v1, where routing, fees and transaction building happen server side
+
+use std::{collections::HashMap, fmt};
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    quote::SwapMode,
+    request_options::{Endpoint, RequestOptions},
+    route_plan_with_metadata::RoutePlanWithMetadata,
+    serde_helpers::{field_as_string, option_field_as_string},
+    swap::{base64_serialize_deserialize, option_base64_serialize_deserialize, parse_pubkey},
+    JupiterError, JupiterSwapApiClient,
+};
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UltraOrderRequest {
+    #[serde(with = "field_as_string")]
+    pub input_mint: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub output_mint: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub amount: u64,
+    /// No transaction is returned without a taker, only the quote
+    #[serde(
+        with = "option_field_as_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub taker: Option<Pubkey>,
+    #[serde(
+        with = "option_field_as_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub referral_account: Option<Pubkey>,
+    /// Referral fee in basis points, collected on top of the Ultra fee
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub referral_fee: Option<u16>,
+}
+
+impl UltraOrderRequest {
+    pub fn new(input_mint: Pubkey, output_mint: Pubkey, amount: u64, taker: Pubkey) -> Self {
+        Self {
+            input_mint,
+            output_mint,
+            amount,
+            taker: Some(taker),
+            referral_account: None,
+            referral_fee: None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UltraOrderResponse {
+    /// Passed to [`JupiterSwapApiClient::execute`] with the signed transaction
+    pub request_id: String,
+    #[serde(with = "field_as_string")]
+    pub input_mint: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub output_mint: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub in_amount: u64,
+    #[serde(with = "field_as_string")]
+    pub out_amount: u64,
+    #[serde(with = "field_as_string")]
+    pub other_amount_threshold: u64,
+    pub swap_mode: SwapMode,
+    pub slippage_bps: u16,
+    pub price_impact_pct: Option<Decimal>,
+    #[serde(default)]
+    pub route_plan: RoutePlanWithMetadata,
+    pub fee_bps: Option<u16>,
+    #[serde(default)]
+    pub prioritization_fee_lamports: Option<u64>,
+    /// e.g. `aggregator` or `rfq`
+    pub swap_type: Option<String>,
+    /// Router serving the order, see [`JupiterSwapApiClient::routers`]
+    pub router: Option<String>,
+    /// The fees are paid by Jupiter
+    #[serde(default)]
+    pub gasless: bool,
+    /// Unsigned versioned transaction, `None` without a taker or when the order can't be filled
+    #[serde(default, with = "option_base64_serialize_deserialize")]
+    pub transaction: Option<Vec<u8>>,
+    /// Why no transaction was returned, e.g. insufficient funds
+    pub error_message: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct UltraExecuteRequest<'a> {
+    #[serde(with = "base64_serialize_deserialize")]
+    signed_transaction: Vec<u8>,
+    request_id: &'a str,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UltraExecuteStatus {
+    Success,
+    Failed,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SwapEvent {
+    #[serde(with = "field_as_string")]
+    pub input_mint: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub input_amount: u64,
+    #[serde(with = "field_as_string")]
+    pub output_mint: Pubkey,
+    #[serde(with = "field_as_string")]
+    pub output_amount: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UltraExecuteResponse {
+    pub status: UltraExecuteStatus,
+    /// 0 on success, see the Ultra API docs for the failure codes
+    pub code: i64,
+    pub signature: Option<String>,
+    #[serde(default, with = "option_field_as_string")]
+    pub slot: Option<u64>,
+    /// Set when the execution failed
+    pub error: Option<String>,
+    #[serde(default, with = "option_field_as_string")]
+    pub input_amount_result: Option<u64>,
+    #[serde(default, with = "option_field_as_string")]
+    pub output_amount_result: Option<u64>,
+    #[serde(default)]
+    pub swap_events: Vec<SwapEvent>,
+}
+
+impl UltraExecuteResponse {
+    pub fn is_success(&self) -> bool {
+        self.status == UltraExecuteStatus::Success
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenBalance {
+    #[serde(with = "field_as_string")]
+    pub amount: u64,
+    pub ui_amount: f64,
+    /// Slot the balance was read at
+    pub slot: u64,
+    pub is_frozen: bool,
+}
+
+/// Key of the native SOL balance in the balances response
+const NATIVE_SOL_KEY: &str = "SOL";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Balances {
+    /// Native SOL, in lamports
+    pub sol: Option<TokenBalance>,
+    /// Token accounts by mint
+    pub tokens: HashMap<Pubkey, TokenBalance>,
+}
+
+/// Kind of a shield warning
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ShieldWarningType {
+    NotVerified,
+    LowLiquidity,
+    NotSellable,
+    LowOrganicActivity,
+    HasFreezeAuthority,
+    HasMintAuthority,
+    HasPermanentDelegate,
+    NewListing,
+    /// Any warning this client doesn't know about yet
+    Other(String),
+}
+
+impl ShieldWarningType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::NotVerified => "NOT_VERIFIED",
+            Self::LowLiquidity => "LOW_LIQUIDITY",
+            Self::NotSellable => "NOT_SELLABLE",
+            Self::LowOrganicActivity => "LOW_ORGANIC_ACTIVITY",
+            Self::HasFreezeAuthority => "HAS_FREEZE_AUTHORITY",
+            Self::HasMintAuthority => "HAS_MINT_AUTHORITY",
+            Self::HasPermanentDelegate => "HAS_PERMANENT_DELEGATE",
+            Self::NewListing => "NEW_LISTING",
+            Self::Other(warning_type) => warning_type,
+        }
+    }
+}
+
+impl From<&str> for ShieldWarningType {
+    fn from(warning_type: &str) -> Self {
+        match warning_type {
+            "NOT_VERIFIED" => Self::NotVerified,
+            "LOW_LIQUIDITY" => Self::LowLiquidity,
+            "NOT_SELLABLE" => Self::NotSellable,
+            "LOW_ORGANIC_ACTIVITY" => Self::LowOrganicActivity,
+            "HAS_FREEZE_AUTHORITY" => Self::HasFreezeAuthority,
+            "HAS_MINT_AUTHORITY" => Self::HasMintAuthority,
+            "HAS_PERMANENT_DELEGATE" => Self::HasPermanentDelegate,
+            "NEW_LISTING" => Self::NewListing,
+            _ => Self::Other(warning_type.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for ShieldWarningType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for ShieldWarningType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ShieldWarningType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self::from(String::deserialize(deserializer)?.as_str()))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum ShieldSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ShieldWarning {
+    #[serde(rename = "type")]
+    pub warning_type: ShieldWarningType,
+    pub message: String,
+    pub severity: ShieldSeverity,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ShieldResponseInternal {
+    warnings: HashMap<String, Vec<ShieldWarning>>,
+}
+
+/// Backend router which may serve Ultra orders
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UltraRouter {
+    /// Matches [`UltraOrderResponse::router`], e.g. `metis` or `jupiterz`
+    pub id: String,
+    pub name: String,
+    pub icon: Option<String>,
+}
+
+impl JupiterSwapApiClient {
+    /// Get an order, with a transaction ready to be signed by the taker
+    pub async fn order(
+        &self,
+        ultra_order_request: &UltraOrderRequest,
+    ) -> Result<UltraOrderResponse, JupiterError> {
+        if ultra_order_request.amount == 0 {
+            return Err(JupiterError::InvalidRequest(
+                "amount has to be greater than 0".to_string(),
+            ));
+        }
+        self.send(
+            Endpoint::UltraOrder,
+            |api_base_path| {
+                self.client
+                    .get(format!("{api_base_path}/ultra/v1/order"))
+                    .query(ultra_order_request)
+            },
+            &RequestOptions::default(),
+        )
+        .await
+        .map(|(ultra_order_response, _)| ultra_order_response)
+    }
+
+    /// Submit the signed transaction of an order, `signed_transaction` is the serialized versioned transaction
+    ///
+    /// A failed execution is reported in the response, see [`UltraExecuteResponse::error`].
+    pub async fn execute(
+        &self,
+        signed_transaction: &[u8],
+        request_id: &str,
+    ) -> Result<UltraExecuteResponse, JupiterError> {
+        let ultra_execute_request = UltraExecuteRequest {
+            signed_transaction: signed_transaction.to_vec(),
+            request_id,
+        };
+        self.send(
+            Endpoint::UltraExecute,
+            |api_base_path| {
+                self.client
+                    .post(format!("{api_base_path}/ultra/v1/execute"))
+                    .json(&ultra_execute_request)
+            },
+            &RequestOptions::default(),
+        )
+        .await
+        .map(|(ultra_execute_response, _)| ultra_execute_response)
+    }
+
+    /// Native SOL and token balances of `wallet`
+    pub async fn balances(&self, wallet: &Pubkey) -> Result<Balances, JupiterError> {
+        let (response, _) = self
+            .send::<HashMap<String, TokenBalance>>(
+                Endpoint::UltraBalances,
+                |api_base_path| {
+                    self.client
+                        .get(format!("{api_base_path}/ultra/v1/balances/{wallet}"))
+                },
+                &RequestOptions::default(),
+            )
+            .await?;
+        let mut balances = Balances::default();
+        for (key, balance) in response {
+            if key == NATIVE_SOL_KEY {
+                balances.sol = Some(balance);
+            } else {
+                balances
+                    .tokens
+                    .insert(parse_pubkey(&key, "balance mint")?, balance);
+            }
+        }
+        Ok(balances)
+    }
+
+    /// Safety warnings of `mints`, mints without warnings may be missing from the result
+    pub async fn shield(
+        &self,
+        mints: &[Pubkey],
+    ) -> Result<HashMap<Pubkey, Vec<ShieldWarning>>, JupiterError> {
+        if mints.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let mints = mints
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        let (response, _) = self
+            .send::<ShieldResponseInternal>(
+                Endpoint::UltraShield,
+                |api_base_path| {
+                    self.client
+                        .get(format!("{api_base_path}/ultra/v1/shield"))
+                        .query(&[("mints", &mints)])
+                },
+                &RequestOptions::default(),
+            )
+            .await?;
+        response
+            .warnings
+            .into_iter()
+            .map(|(mint, warnings)| Ok((parse_pubkey(&mint, "shield mint")?, warnings)))
+            .collect()
+    }
+
+    pub async fn routers(&self) -> Result<Vec<UltraRouter>, JupiterError> {
+        self.send(
+            Endpoint::UltraRouters,
+            |api_base_path| {
+                self.client
+                    .get(format!("{api_base_path}/ultra/v1/order/routers"))
+            },
+            &RequestOptions::default(),
+        )
+        .await
+        .map(|(routers, _)| routers)
+    }
+}